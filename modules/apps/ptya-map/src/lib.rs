@@ -9,7 +9,7 @@ use crate::viewport::Viewport;
 use ahash::AHashSet;
 use anyways::ext::AuditExt;
 use anyways::Result;
-use egui::{Color32, Id, PaintCallback, Painter, Pos2, Rgba, Rounding, Sense, Stroke, Ui, Vec2};
+use egui::{Color32, Id, PaintCallback, Painter, Pos2, Rgba, Rounding, Stroke, Ui, Vec2};
 use glium::backend::Context;
 use glium::framebuffer::SimpleFrameBuffer;
 use glium::Surface;
@@ -20,6 +20,7 @@ use mathie::{Rect, Vec2D};
 use ptya_core::app::{App, Manifest, Version};
 use ptya_core::color::{ColorTag, Theme};
 use ptya_core::ui::components::Button;
+use ptya_core::ui::gesture::GestureSense;
 use ptya_core::ui::Pui;
 use ptya_core::System;
 use ptya_icon::icon;
@@ -47,6 +48,7 @@ pub fn manifest() -> Manifest {
 		name: "Map".to_string(),
 		icon: icon!("map"),
 		version: Version::new(0, 0, 0),
+		colors: vec![],
 	}
 }
 
@@ -92,15 +94,30 @@ impl App for Map {
 		fb.clear_color_srgb(bg.r(), bg.g(), bg.b(), 1.0);
 
 		let rect = ui.clip_rect();
-		let response = ui.interact(rect, ui.id().with("hello"), Sense::click_and_drag());
-		let drag_delta = response.drag_delta();
+		let gestures = ui.gestures(
+			ui.id().with("map"),
+			rect,
+			GestureSense {
+				drag: true,
+				pinch: true,
+				double_tap: true,
+				..Default::default()
+			},
+		);
+		// Two fingers move the map while they zoom it.
+		let drag_delta = gestures.drag_delta() + gestures.pinch_translation();
 		let scale = 2f64.powf(self.viewer.zoom as f64);
 		//info!("{scale}");
 		self.viewer.x -= ((drag_delta.x as f64 / rect.height() as f64) / scale) * 2.0;
 		self.viewer.y -= ((drag_delta.y as f64 / rect.height() as f64) / scale) * 2.0;
 
-		if let Some(hover) = response.hover_pos() {
-			self.viewer.zoom += response.ctx.input().scroll_delta.y as f64 / 250.0;
+		// The zoom level is the power of two of the scale, so zoom factors are added as logarithms.
+		self.viewer.zoom += (gestures.zoom() as f64).log2();
+		if gestures.double_tapped().is_some() {
+			self.viewer.zoom += 1.0;
+		}
+		if ui.rect_contains_pointer(rect) {
+			self.viewer.zoom += ui.input().scroll_delta.y as f64 / 250.0;
 		}
 
 		let (width, height) = fb.get_dimensions();
@@ -112,7 +129,11 @@ impl App for Map {
 	}
 
 	fn update(&mut self, system: &System) {
-		self.styler.write().unwrap().update_theme(system.color.theme());
+		self.styler.write().unwrap().update_theme(system.color.target_theme());
+	}
+
+	fn theme_changed(&mut self, theme: &Theme) {
+		self.styler.write().unwrap().update_theme(theme);
 	}
 }
 
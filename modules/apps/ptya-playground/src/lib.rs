@@ -4,7 +4,10 @@ use ptya_core::app::{App, Manifest, Version};
 use ptya_core::color::ColorTag;
 use ptya_core::{layout, System};
 use ptya_core::ui::{Pui, ROUNDING};
-use ptya_core::ui::components::{Button, Slider};
+use ptya_core::ui::components::{
+	BottomSheet, Button, Dialog, Pages, RangeSlider, SegmentedButton, Slider, Snackbar, Tab, TabBar, TextField, Toggle,
+	VirtualList,
+};
 use ptya_icon::icon;
 
 pub fn manifest() -> Manifest {
@@ -13,22 +16,94 @@ pub fn manifest() -> Manifest {
 		name: "Playground".to_string(),
 		icon: icon!("attractions"),
 		version: Version::new(0, 1, 1),
+		colors: vec![("eco", [0x4c, 0xaf, 0x50])],
 	}
 }
 
 pub fn load() -> Box<dyn App> {
-	Box::new(PlaygroundApp {})
+	Box::new(PlaygroundApp {
+		search: String::new(),
+		temperature: 21.0,
+		dark: true,
+		delete_dialog: false,
+		deleted: false,
+		sheet: false,
+		tab: 0,
+		units: 0,
+	})
 }
 
-pub struct PlaygroundApp {}
+pub struct PlaygroundApp {
+	search: String,
+	temperature: f32,
+	dark: bool,
+	delete_dialog: bool,
+	deleted: bool,
+	sheet: bool,
+	tab: usize,
+	units: usize,
+}
 
 impl App for PlaygroundApp {
 	fn tick(&mut self, ui: &mut Pui, fb: &mut SimpleFrameBuffer) {
 		layout!(ui => horizontal {
 			Button::new("hello", ColorTag::Blue).ui(ui);
+			Button::new("eco", ColorTag::Custom("eco")).ui(ui);
 			Slider::new("hello", false).show(ui);
 			Slider::new("answer", true).show(ui);
 		});
+		TextField::new(&mut self.search, "Search").show(ui);
+		layout!(ui => horizontal {
+			let mut temperature = RangeSlider::new(&mut self.temperature, 16.0..=28.0);
+			temperature.step = Some(0.5);
+			temperature.decimals = 1;
+			temperature.suffix = "°C".to_string();
+			temperature.show(ui);
+			Toggle::new(&mut self.dark, "Dark mode").show(ui);
+		});
+		layout!(ui => horizontal {
+			if Button::new("Delete route", ColorTag::Red).ui(ui).clicked() {
+				self.delete_dialog = true;
+			}
+			if Button::new("Details", ColorTag::Tertiary).ui(ui).clicked() {
+				self.sheet = true;
+			}
+		});
+		let tabs = vec![
+			Tab::with_icon(icon!("search"), "Results"),
+			Tab::with_icon(icon!("tune"), "Settings"),
+		];
+		TabBar::new("tabs", &mut self.tab, tabs).show(ui);
+		let units = &mut self.units;
+		let mut pages = Pages::new("pages", &mut self.tab, 2);
+		pages.height = Some(400.0);
+		pages.show(ui, |ui, page| match page {
+			0 => {
+				let mut results = VirtualList::new("results", 10_000, 50.0);
+				results.height = Some(400.0);
+				results.show(ui, |ui, row| {
+					ui.label(format!("Result {row}"));
+				});
+			}
+			_ => {
+				SegmentedButton::new("units", units, vec![Tab::new("Metric"), Tab::new("Imperial")]).show(ui);
+			}
+		});
+
+		let mut dialog = Dialog::new(
+			"delete_route",
+			"Delete saved route?",
+			"The route to work will be removed from your saved routes.",
+			"Delete",
+		);
+		dialog.color = ColorTag::Red;
+		if dialog.show(ui, &mut self.delete_dialog).confirmed() {
+			self.deleted = true;
+		}
+		BottomSheet::new("details", 600.0).show(ui, &mut self.sheet, |ui| {
+			ui.label("Route details");
+		});
+		Snackbar::with_action("deleted", "Route deleted", "Undo").show(ui, &mut self.deleted);
 		//ui.painter().rect_filled(ui.max_rect(), 0.0, ui.color().ascend(10.0).tag_bg(ColorTag::Red));
 		//if ui.interact(ui.max_rect(), ui.id().with("69420"), Sense::click_and_drag()).hovered() {
 		//	println!("hover playground");
@@ -0,0 +1,173 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+	parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, Lit, Meta,
+	NestedMeta, Path, Result,
+};
+
+/// Derives `Lerp` by interpolating every field.
+///
+/// Fields can be marked with `#[lerp(skip)]` to take the target value right away,
+/// or with `#[lerp(snap)]` to jump from the start to the target value halfway through.
+/// Enums interpolate between values of the same variant and snap between different variants.
+///
+/// The generated impl uses the `Lerp` trait that is in scope,
+/// use `#[lerp(crate = "ptya_core::animation")]` on the type to point it somewhere else.
+#[proc_macro_derive(Lerp, attributes(lerp))]
+pub fn derive_lerp(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input)
+		.unwrap_or_else(Error::into_compile_error)
+		.into()
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum FieldMode {
+	Lerp,
+	Skip,
+	Snap,
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream> {
+	let lerp: Path = match container_crate(&input.attrs)? {
+		Some(path) => parse_quote!(#path::Lerp),
+		None => parse_quote!(Lerp),
+	};
+
+	let params: Vec<_> = input.generics.type_params().map(|param| param.ident.clone()).collect();
+	let where_clause = input.generics.make_where_clause();
+	for param in params {
+		where_clause.predicates.push(parse_quote!(#param: #lerp));
+	}
+
+	let body = match &input.data {
+		Data::Struct(data) => {
+			let (from, to, construct) = bindings(&data.fields, &lerp)?;
+			quote! {
+				let Self #from = v0;
+				let Self #to = v1;
+				Self #construct
+			}
+		}
+		Data::Enum(data) => {
+			let mut arms = vec![];
+			for variant in &data.variants {
+				let name = &variant.ident;
+				let (from, to, construct) = bindings(&variant.fields, &lerp)?;
+				arms.push(quote! {
+					(Self::#name #from, Self::#name #to) => Self::#name #construct,
+				});
+			}
+			quote! {
+				#[allow(unreachable_patterns)]
+				match (v0, v1) {
+					#(#arms)*
+					_ if t < 0.5 => ::core::clone::Clone::clone(v0),
+					_ => ::core::clone::Clone::clone(v1),
+				}
+			}
+		}
+		Data::Union(data) => {
+			return Err(Error::new_spanned(
+				data.union_token,
+				"Lerp can not be derived for unions",
+			))
+		}
+	};
+
+	let name = &input.ident;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics #lerp for #name #type_generics #where_clause {
+			#[allow(unused_variables)]
+			fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+				#body
+			}
+		}
+	})
+}
+
+/// Creates the patterns that bind the fields of both values, and the expression that builds the interpolated value.
+fn bindings(fields: &Fields, lerp: &Path) -> Result<(TokenStream, TokenStream, TokenStream)> {
+	let mut from_bindings = vec![];
+	let mut to_bindings = vec![];
+	let mut values = vec![];
+	for (i, field) in fields.iter().enumerate() {
+		let v0 = format_ident!("from_{i}");
+		let v1 = format_ident!("to_{i}");
+		let ty = &field.ty;
+		values.push(match field_mode(&field.attrs)? {
+			FieldMode::Lerp => quote!(<#ty as #lerp>::lerp_static(#v0, #v1, t)),
+			FieldMode::Skip => quote!(::core::clone::Clone::clone(#v1)),
+			FieldMode::Snap => quote! {
+				if t < 0.5 {
+					::core::clone::Clone::clone(#v0)
+				} else {
+					::core::clone::Clone::clone(#v1)
+				}
+			},
+		});
+		from_bindings.push(v0);
+		to_bindings.push(v1);
+	}
+
+	Ok(match fields {
+		Fields::Named(named) => {
+			let names: Vec<_> = named.named.iter().map(|field| &field.ident).collect();
+			(
+				quote!({ #(#names: #from_bindings),* }),
+				quote!({ #(#names: #to_bindings),* }),
+				quote!({ #(#names: #values),* }),
+			)
+		}
+		Fields::Unnamed(_) => (
+			quote!((#(#from_bindings),*)),
+			quote!((#(#to_bindings),*)),
+			quote!((#(#values),*)),
+		),
+		Fields::Unit => (quote!(), quote!(), quote!()),
+	})
+}
+
+fn lerp_attributes(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+	let mut nested = vec![];
+	for attr in attrs.iter().filter(|attr| attr.path.is_ident("lerp")) {
+		match attr.parse_meta()? {
+			Meta::List(list) => nested.extend(list.nested),
+			meta => return Err(Error::new_spanned(meta, "Expected #[lerp(...)]")),
+		}
+	}
+	Ok(nested)
+}
+
+fn container_crate(attrs: &[Attribute]) -> Result<Option<Path>> {
+	let mut path = None;
+	for meta in lerp_attributes(attrs)? {
+		match meta {
+			NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("crate") => {
+				match &value.lit {
+					Lit::Str(lit) => path = Some(lit.parse()?),
+					lit => return Err(Error::new_spanned(lit, "Expected a path in a string")),
+				}
+			}
+			meta => return Err(Error::new_spanned(meta, "Unknown lerp attribute")),
+		}
+	}
+	Ok(path)
+}
+
+fn field_mode(attrs: &[Attribute]) -> Result<FieldMode> {
+	let mut mode = FieldMode::Lerp;
+	for meta in lerp_attributes(attrs)? {
+		let new = match &meta {
+			NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => FieldMode::Skip,
+			NestedMeta::Meta(Meta::Path(path)) if path.is_ident("snap") => FieldMode::Snap,
+			meta => return Err(Error::new_spanned(meta, "Expected skip or snap")),
+		};
+		if mode != FieldMode::Lerp {
+			return Err(Error::new_spanned(meta, "A field can only be skipped or snapped"));
+		}
+		mode = new;
+	}
+	Ok(mode)
+}
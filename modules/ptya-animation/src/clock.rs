@@ -0,0 +1,120 @@
+/// The source of time for the [AnimationManager](crate::AnimationManager).
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Clock {
+	/// Follows the real time of the ui.
+	#[default]
+	RealTime,
+	/// Only moves when it gets advanced, used to test animations.
+	Manual(f64),
+	/// Moves a fixed step every tick no matter how long the frame took.
+	Stepped { time: f64, step: f64 },
+	/// Plays back the frame times of a recording, holding the last frame when it runs out.
+	Replay { frames: Vec<f64>, frame: usize },
+}
+
+impl Clock {
+	pub fn manual() -> Clock {
+		Clock::Manual(0.0)
+	}
+
+	/// A clock that moves as if the ui runs at a fixed frame rate.
+	pub fn stepped(fps: f64) -> Clock {
+		Clock::Stepped {
+			time: 0.0,
+			step: 1.0 / fps,
+		}
+	}
+
+	pub fn replay(frames: Vec<f64>) -> Clock {
+		Clock::Replay { frames, frame: 0 }
+	}
+
+	/// Moves a manual clock forward.
+	pub fn advance(&mut self, seconds: f64) {
+		if let Clock::Manual(time) = self {
+			*time += seconds;
+		}
+	}
+
+	/// Gets the time for a new frame.
+	pub(crate) fn tick(&mut self, real_time: f64) -> f64 {
+		match self {
+			Clock::RealTime => real_time,
+			Clock::Manual(time) => *time,
+			Clock::Stepped { time, step } => {
+				*time += *step;
+				*time
+			}
+			Clock::Replay { frames, frame } => {
+				let time = frames
+					.get(*frame)
+					.or_else(|| frames.last())
+					.copied()
+					.unwrap_or(0.0);
+				*frame += 1;
+				time
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::config::AnimationConfig;
+	use crate::{AnimationManager, AnimationRef, Clock, Easing, Spring};
+	use egui::Id;
+
+	fn manager() -> AnimationManager {
+		AnimationManager::with_clock(AnimationConfig::default(), Clock::manual())
+	}
+
+	#[test]
+	fn manual_clock() {
+		let manager = manager();
+		let id = AnimationRef::<f32>::new(Id::new("test"));
+		manager
+			.get_ref(id)
+			.set_easing(Easing::Linear)
+			.set_to(1.0)
+			.begin();
+
+		let speed = AnimationConfig::default_animation_speed() as f64;
+		manager.advance(speed / 4.0);
+		assert_eq!(manager.get_ref(id).get_value(), 0.25);
+		manager.advance(speed / 4.0);
+		assert_eq!(manager.get_ref(id).get_value(), 0.5);
+		assert!(manager.get_ref(id).is_active());
+		manager.advance(speed);
+		assert_eq!(manager.get_ref(id).get_value(), 1.0);
+		assert!(manager.get_ref(id).is_finished());
+	}
+
+	#[test]
+	fn replay_matches_recording() {
+		let play = |manager: &AnimationManager| -> Vec<f32> {
+			let id = AnimationRef::<f32>::new(Id::new("spring"));
+			manager.update(0.0);
+			manager
+				.get_ref(id)
+				.set_spring(Spring::BOUNCY)
+				.set_to(1.0)
+				.begin();
+			(0..30)
+				.map(|_| {
+					manager.update(0.0);
+					manager.get_ref(id).get_value()
+				})
+				.collect()
+		};
+
+		let recorded = AnimationManager::with_clock(AnimationConfig::default(), Clock::stepped(60.0));
+		recorded.start_recording();
+		let values = play(&recorded);
+		let frames = recorded.stop_recording();
+		assert_eq!(frames.len(), 31);
+
+		let replayed = AnimationManager::with_clock(AnimationConfig::default(), Clock::replay(frames));
+		assert_eq!(values, play(&replayed));
+		assert_ne!(values.first(), values.last());
+	}
+}
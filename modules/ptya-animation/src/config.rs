@@ -1,13 +1,76 @@
+use crate::easing::{Easing, Motion};
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize)]
 pub struct AnimationConfig {
 	#[serde(default = "AnimationConfig::default_animation_speed")]
-	pub animation_speed: f32
+	pub animation_speed: f32,
+	#[serde(default)]
+	pub easing: EasingConfig,
+	/// Animations that have not been used for this many seconds get removed, 0 keeps them forever.
+	#[serde(default = "AnimationConfig::default_stale_seconds")]
+	pub stale_seconds: f64,
+	/// Replaces large moving and resizing animations with fades, for drivers that get motion sick.
+	#[serde(default)]
+	pub reduced_motion: bool,
 }
 
 impl AnimationConfig {
 	pub fn default_animation_speed() -> f32 {
 		0.25
 	}
-}
\ No newline at end of file
+
+	pub fn default_stale_seconds() -> f64 {
+		10.0
+	}
+}
+
+/// Uses the same values as a config file that leaves out every field.
+impl Default for AnimationConfig {
+	fn default() -> Self {
+		AnimationConfig {
+			animation_speed: Self::default_animation_speed(),
+			easing: EasingConfig::default(),
+			stale_seconds: Self::default_stale_seconds(),
+			reduced_motion: false,
+		}
+	}
+}
+
+/// The easing curves used for each [Motion], this allows tuning the motion without touching code.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct EasingConfig {
+	pub standard: Easing,
+	pub emphasized: Easing,
+	pub decelerate: Easing,
+	pub accelerate: Easing,
+}
+
+impl EasingConfig {
+	pub fn get(&self, motion: Motion) -> Easing {
+		let easing = match motion {
+			Motion::Standard => self.standard,
+			Motion::Emphasized => self.emphasized,
+			Motion::Decelerate => self.decelerate,
+			Motion::Accelerate => self.accelerate,
+		};
+
+		// A motion pointing to another motion would never resolve.
+		match easing {
+			Easing::Motion(_) => Easing::Linear,
+			easing => easing,
+		}
+	}
+}
+
+impl Default for EasingConfig {
+	fn default() -> Self {
+		EasingConfig {
+			standard: Easing::STANDARD,
+			emphasized: Easing::Emphasized,
+			decelerate: Easing::EMPHASIZED_DECELERATE,
+			accelerate: Easing::EMPHASIZED_ACCELERATE,
+		}
+	}
+}
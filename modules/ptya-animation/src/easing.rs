@@ -1,16 +1,71 @@
-#[derive(Copy, Clone)]
+use crate::config::EasingConfig;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Easing {
 	Linear,
 	// Quart (its a touch more aggressive than cubic)
 	EaseIn,
 	EaseOut,
 	EaseInOut,
+	/// A css style cubic bezier from (0, 0) to (1, 1) with the control points `[x1, y1, x2, y2]`.
+	CubicBezier([f32; 4]),
+	/// The material 3 emphasized curve, this is two beziers joined together.
+	Emphasized,
+	// Overshoots slightly past the target
+	BackIn,
+	BackOut,
+	BackInOut,
+	// Springs around the target
+	ElasticIn,
+	ElasticOut,
+	// Bounces off the target like a ball
+	BounceIn,
+	BounceOut,
+	/// Jumps between a fixed amount of steps.
+	Steps(u32),
+	/// Uses the easing configured for this kind of motion in the [AnimationConfig](crate::config::AnimationConfig).
+	Motion(Motion),
+}
+
+/// The kinds of motion that designers can tune in the [AnimationConfig](crate::config::AnimationConfig).
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Motion {
+	/// For anything that begins and ends on screen.
+	Standard,
+	/// For large and expressive transitions.
+	Emphasized,
+	/// For things entering the screen.
+	Decelerate,
+	/// For things leaving the screen.
+	Accelerate,
 }
 
 impl Easing {
+	pub const STANDARD: Easing = Easing::CubicBezier([0.2, 0.0, 0.0, 1.0]);
+	pub const STANDARD_DECELERATE: Easing = Easing::CubicBezier([0.0, 0.0, 0.0, 1.0]);
+	pub const STANDARD_ACCELERATE: Easing = Easing::CubicBezier([0.3, 0.0, 1.0, 1.0]);
+	pub const EMPHASIZED_DECELERATE: Easing = Easing::CubicBezier([0.05, 0.7, 0.1, 1.0]);
+	pub const EMPHASIZED_ACCELERATE: Easing = Easing::CubicBezier([0.3, 0.0, 0.8, 0.15]);
+
+	/// Resolves [Easing::Motion] to the curve configured for it.
+	pub fn resolve(self, config: &EasingConfig) -> Easing {
+		match self {
+			Easing::Motion(motion) => config.get(motion),
+			easing => easing,
+		}
+	}
+
+	/// Applies the easing to a position between 0 and 1.
+	/// Out of range positions get clamped, the output may go outside 0 and 1 for curves that overshoot.
 	#[inline(always)]
 	pub fn apply(&self, x: f64) -> f64 {
-		assert!((0.0..=1.0).contains(&x));
+		if x.is_nan() {
+			return 1.0;
+		}
+
+		let x = x.clamp(0.0, 1.0);
 		match self {
 			Easing::Linear => x,
 			Easing::EaseIn => x * x * x * x,
@@ -22,6 +77,179 @@ impl Easing {
 					1.0 - (-2.0 * x + 2.0).powf(4.0) / 2.0
 				}
 			}
+			Easing::CubicBezier([x1, y1, x2, y2]) => {
+				cubic_bezier(x, *x1 as f64, *y1 as f64, *x2 as f64, *y2 as f64)
+			}
+			Easing::Emphasized => {
+				// M 0,0 C 0.05,0 0.133333,0.06 0.166666,0.4 C 0.208333,0.82 0.25,1 1,1
+				const SPLIT_X: f64 = 0.166666;
+				const SPLIT_Y: f64 = 0.4;
+				if x < SPLIT_X {
+					let t = x / SPLIT_X;
+					cubic_bezier(t, 0.05 / SPLIT_X, 0.0, 0.133333 / SPLIT_X, 0.06 / SPLIT_Y) * SPLIT_Y
+				} else {
+					let w = 1.0 - SPLIT_X;
+					let h = 1.0 - SPLIT_Y;
+					let t = (x - SPLIT_X) / w;
+					SPLIT_Y
+						+ cubic_bezier(
+							t,
+							(0.208333 - SPLIT_X) / w,
+							(0.82 - SPLIT_Y) / h,
+							(0.25 - SPLIT_X) / w,
+							1.0,
+						) * h
+				}
+			}
+			Easing::BackIn => back_in(x),
+			Easing::BackOut => 1.0 - back_in(1.0 - x),
+			Easing::BackInOut => {
+				const C: f64 = 1.70158 * 1.525;
+				if x < 0.5 {
+					((2.0 * x).powi(2) * ((C + 1.0) * 2.0 * x - C)) / 2.0
+				} else {
+					((2.0 * x - 2.0).powi(2) * ((C + 1.0) * (x * 2.0 - 2.0) + C) + 2.0) / 2.0
+				}
+			}
+			Easing::ElasticIn => 1.0 - elastic_out(1.0 - x),
+			Easing::ElasticOut => elastic_out(x),
+			Easing::BounceIn => 1.0 - bounce_out(1.0 - x),
+			Easing::BounceOut => bounce_out(x),
+			Easing::Steps(steps) => {
+				let steps = (*steps).max(1) as f64;
+				(x * steps).floor() / steps
+			}
+			Easing::Motion(motion) => EasingConfig::default().get(*motion).apply(x),
 		}
 	}
 }
+
+fn back_in(x: f64) -> f64 {
+	const C: f64 = 1.70158;
+	(C + 1.0) * x * x * x - C * x * x
+}
+
+fn elastic_out(x: f64) -> f64 {
+	if x == 0.0 || x == 1.0 {
+		x
+	} else {
+		2f64.powf(-10.0 * x) * ((x * 10.0 - 0.75) * (2.0 * PI) / 3.0).sin() + 1.0
+	}
+}
+
+fn bounce_out(x: f64) -> f64 {
+	const N: f64 = 7.5625;
+	const D: f64 = 2.75;
+	if x < 1.0 / D {
+		N * x * x
+	} else if x < 2.0 / D {
+		let x = x - 1.5 / D;
+		N * x * x + 0.75
+	} else if x < 2.5 / D {
+		let x = x - 2.25 / D;
+		N * x * x + 0.9375
+	} else {
+		let x = x - 2.625 / D;
+		N * x * x + 0.984375
+	}
+}
+
+/// Evaluates a cubic bezier going from (0, 0) to (1, 1) at `x`.
+fn cubic_bezier(x: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+	let bezier = |t: f64, p1: f64, p2: f64| {
+		let inv = 1.0 - t;
+		3.0 * inv * inv * t * p1 + 3.0 * inv * t * t * p2 + t * t * t
+	};
+
+	// Find the t that lands on x, newton first and bisection if that does not converge.
+	let mut t = x;
+	for _ in 0..8 {
+		let error = bezier(t, x1, x2) - x;
+		if error.abs() < 1e-7 {
+			return bezier(t, y1, y2);
+		}
+
+		let inv = 1.0 - t;
+		let slope = 3.0 * inv * inv * x1 + 6.0 * inv * t * (x2 - x1) + 3.0 * t * t * (1.0 - x2);
+		if slope.abs() < 1e-6 {
+			break;
+		}
+		t = (t - error / slope).clamp(0.0, 1.0);
+	}
+
+	let (mut low, mut high) = (0.0, 1.0);
+	t = x;
+	for _ in 0..32 {
+		let value = bezier(t, x1, x2);
+		if (value - x).abs() < 1e-7 {
+			break;
+		}
+		if value < x {
+			low = t;
+		} else {
+			high = t;
+		}
+		t = (low + high) / 2.0;
+	}
+	bezier(t, y1, y2)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CURVES: [Easing; 12] = [
+		Easing::Linear,
+		Easing::EaseInOut,
+		Easing::STANDARD,
+		Easing::EMPHASIZED_DECELERATE,
+		Easing::EMPHASIZED_ACCELERATE,
+		Easing::Emphasized,
+		Easing::BackInOut,
+		Easing::ElasticIn,
+		Easing::ElasticOut,
+		Easing::BounceIn,
+		Easing::BounceOut,
+		Easing::Steps(4),
+	];
+
+	#[test]
+	fn endpoints() {
+		for easing in CURVES {
+			assert!(easing.apply(0.0).abs() < 1e-4, "{easing:?} does not start at 0");
+			assert!((easing.apply(1.0) - 1.0).abs() < 1e-4, "{easing:?} does not end at 1");
+		}
+	}
+
+	#[test]
+	fn out_of_range() {
+		for easing in CURVES {
+			assert_eq!(easing.apply(-1.0), easing.apply(0.0));
+			assert_eq!(easing.apply(2.0), easing.apply(1.0));
+			assert_eq!(easing.apply(f64::NAN), 1.0);
+		}
+	}
+
+	#[test]
+	fn cubic_bezier_matches_known_points() {
+		// The linear bezier is a straight line.
+		let linear = Easing::CubicBezier([0.25, 0.25, 0.75, 0.75]);
+		for i in 0..=10 {
+			let x = i as f64 / 10.0;
+			assert!((linear.apply(x) - x).abs() < 1e-5);
+		}
+
+		// Emphasized is continuous where the two segments meet.
+		let split = 0.166666;
+		assert!((Easing::Emphasized.apply(split - 1e-6) - Easing::Emphasized.apply(split)).abs() < 1e-3);
+	}
+
+	#[test]
+	fn motion_resolves_through_config() {
+		let config = EasingConfig {
+			decelerate: Easing::Linear,
+			..EasingConfig::default()
+		};
+		assert_eq!(Easing::Motion(Motion::Decelerate).resolve(&config), Easing::Linear);
+	}
+}
@@ -1,7 +1,8 @@
 use egui::{Color32, Pos2, Rect, Rounding, Stroke, Vec2};
 use palette::convert::FromColorUnclamped;
-use palette::{Lab, Srgb};
+use palette::{Hsv, Lab, LinSrgb, Mix, Oklab, Oklch, Srgb};
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::ops::Range;
 
 pub trait Lerp: PartialEq + Clone + Any {
@@ -9,24 +10,53 @@ pub trait Lerp: PartialEq + Clone + Any {
 		Self::lerp_static(self, to, t)
 	}
 	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self;
+
+	/// The dot product of the span from `v0` to `v1` and the span from `w0` to `w1`, [None] for values without a distance.
+	/// Springs use it to keep their speed when their target changes.
+	fn span_dot(_v0: &Self, _v1: &Self, _w0: &Self, _w1: &Self) -> Option<f64> {
+		None
+	}
 }
 
 impl Lerp for f32 {
 	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
 		((v1 - v0) * t) + v0
 	}
+
+	fn span_dot(v0: &Self, v1: &Self, w0: &Self, w1: &Self) -> Option<f64> {
+		Some((v1 - v0) as f64 * (w1 - w0) as f64)
+	}
+}
+
+impl Lerp for f64 {
+	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+		((v1 - v0) * t as f64) + v0
+	}
+
+	fn span_dot(v0: &Self, v1: &Self, w0: &Self, w1: &Self) -> Option<f64> {
+		Some((v1 - v0) * (w1 - w0))
+	}
 }
 
 impl Lerp for Pos2 {
 	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
 		(((*v1 - *v0) * t) + v0.to_vec2()).to_pos2()
 	}
+
+	fn span_dot(v0: &Self, v1: &Self, w0: &Self, w1: &Self) -> Option<f64> {
+		Vec2::span_dot(&v0.to_vec2(), &v1.to_vec2(), &w0.to_vec2(), &w1.to_vec2())
+	}
 }
 
 impl Lerp for Vec2 {
 	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
 		((*v1 - *v0) * t) + *v0
 	}
+
+	fn span_dot(v0: &Self, v1: &Self, w0: &Self, w1: &Self) -> Option<f64> {
+		let (v, w) = (*v1 - *v0, *w1 - *w0);
+		Some(v.x as f64 * w.x as f64 + v.y as f64 * w.y as f64)
+	}
 }
 
 impl Lerp for Rounding {
@@ -46,6 +76,12 @@ impl Lerp for Rect {
 		let center = Vec2::lerp_static(&v0.center().to_vec2(), &v1.center().to_vec2(), t);
 		Rect::from_center_size(center.to_pos2(), size)
 	}
+
+	fn span_dot(v0: &Self, v1: &Self, w0: &Self, w1: &Self) -> Option<f64> {
+		let center = Pos2::span_dot(&v0.center(), &v1.center(), &w0.center(), &w1.center())?;
+		let size = Vec2::span_dot(&v0.size(), &v1.size(), &w0.size(), &w1.size())?;
+		Some(center + size)
+	}
 }
 
 impl Lerp for Color32 {
@@ -73,6 +109,110 @@ impl Lerp for Stroke {
 	}
 }
 
+/// Interpolates between two values if both are [Some], otherwise jumps halfway through.
+impl<L: Lerp> Lerp for Option<L> {
+	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+		match (v0, v1) {
+			(Some(v0), Some(v1)) => Some(v0.lerp(v1, t)),
+			_ => snap(v0, v1, t),
+		}
+	}
+}
+
+/// Lerps the entries both maps have, entries that only the target has show up right away.
+impl<K: Ord + Clone + Any, L: Lerp> Lerp for BTreeMap<K, L> {
+	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+		v1.iter()
+			.map(|(key, to)| {
+				let value = match v0.get(key) {
+					Some(from) => from.lerp(to, t),
+					None => to.clone(),
+				};
+				(key.clone(), value)
+			})
+			.collect()
+	}
+}
+
+impl<L: Lerp, const N: usize> Lerp for [L; N] {
+	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+		std::array::from_fn(|i| v0[i].lerp(&v1[i], t))
+	}
+}
+
+macro_rules! lerp_tuple {
+	($($name:ident $index:tt),+) => {
+		impl<$($name: Lerp),+> Lerp for ($($name,)+) {
+			fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+				($(v0.$index.lerp(&v1.$index, t),)+)
+			}
+		}
+	};
+}
+
+lerp_tuple!(A 0);
+lerp_tuple!(A 0, B 1);
+lerp_tuple!(A 0, B 1, C 2);
+lerp_tuple!(A 0, B 1, C 2, D 3);
+lerp_tuple!(A 0, B 1, C 2, D 3, E 4);
+lerp_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+macro_rules! color_space {
+	($(#[$doc:meta])* $name:ident, $space:ty) => {
+		$(#[$doc])*
+		#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+		pub struct $name(pub Color32);
+
+		impl Lerp for $name {
+			fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
+				$name(mix::<$space>(v0.0, v1.0, t))
+			}
+		}
+
+		impl From<Color32> for $name {
+			fn from(color: Color32) -> Self {
+				$name(color)
+			}
+		}
+
+		impl From<$name> for Color32 {
+			fn from(color: $name) -> Self {
+				color.0
+			}
+		}
+	};
+}
+
+color_space!(
+	/// A [Color32] that is interpolated in linear rgb, like light mixing.
+	LinearColor,
+	LinSrgb
+);
+color_space!(
+	/// A [Color32] that is interpolated in Oklab, this keeps the perceived brightness more even than Lab.
+	OklabColor,
+	Oklab
+);
+color_space!(
+	/// A [Color32] that is interpolated around the hue wheel in Oklch, this keeps colors saturated halfway through.
+	OklchColor,
+	Oklch
+);
+color_space!(
+	/// A [Color32] that is interpolated around the hue wheel in hsv.
+	HsvColor,
+	Hsv
+);
+
+/// Jumps from `v0` to `v1` halfway through, for values that can not be interpolated.
+pub fn snap<T: Clone>(v0: &T, v1: &T, t: f32) -> T {
+	if t < 0.5 {
+		v0.clone()
+	} else {
+		v1.clone()
+	}
+}
+
 pub fn extend(range: Range<f32>, t: f32) -> f32 {
 	let t = t - range.start;
 	let t = t / (range.end - range.start);
@@ -90,3 +230,93 @@ fn from_lab(lab: Lab, a: u8) -> Color32 {
 	let rgb1: Srgb<u8> = rgb2.into_format();
 	Color32::from_rgba_premultiplied(rgb1.red, rgb1.green, rgb1.blue, a)
 }
+
+/// Interpolates two colors in another color space, the alpha is always interpolated linearly.
+fn mix<C>(v0: Color32, v1: Color32, t: f32) -> Color32
+where
+	C: Mix<Scalar = f32> + FromColorUnclamped<Srgb>,
+	Srgb: FromColorUnclamped<C>,
+{
+	let convert = |color: Color32| {
+		let rgb: Srgb = Srgb::new(color.r(), color.g(), color.b()).into_format();
+		C::from_color_unclamped(rgb)
+	};
+	let rgb = Srgb::from_color_unclamped(convert(v0).mix(&convert(v1), t));
+	let rgb: Srgb<u8> = rgb.into_format();
+	let a = (v0.a() as f32).lerp(&(v1.a() as f32), t) as u8;
+	Color32::from_rgba_premultiplied(rgb.red, rgb.green, rgb.blue, a)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Lerp, OklchColor};
+	use egui::{Color32, Vec2};
+	use std::collections::BTreeMap;
+
+	#[derive(Clone, PartialEq, Debug, Lerp)]
+	struct Panel {
+		offset: Vec2,
+		opacity: (f32, f64),
+		#[lerp(snap)]
+		title: String,
+		#[lerp(skip)]
+		id: u32,
+	}
+
+	#[derive(Clone, PartialEq, Debug, Lerp)]
+	enum Shape {
+		Circle(f32),
+		Square { size: [f32; 2] },
+		Hidden,
+	}
+
+	#[test]
+	fn derive_struct() {
+		let from = Panel {
+			offset: Vec2::ZERO,
+			opacity: (0.0, 0.0),
+			title: "from".into(),
+			id: 0,
+		};
+		let to = Panel {
+			offset: Vec2::new(10.0, 20.0),
+			opacity: (1.0, 2.0),
+			title: "to".into(),
+			id: 1,
+		};
+
+		let quarter = from.lerp(&to, 0.25);
+		assert_eq!(quarter.offset, Vec2::new(2.5, 5.0));
+		assert_eq!(quarter.opacity, (0.25, 0.5));
+		assert_eq!(quarter.title, "from");
+		assert_eq!(quarter.id, 1);
+		assert_eq!(from.lerp(&to, 0.75).title, "to");
+	}
+
+	#[test]
+	fn derive_enum() {
+		assert_eq!(Shape::Circle(0.0).lerp(&Shape::Circle(2.0), 0.5), Shape::Circle(1.0));
+		assert_eq!(
+			Shape::Square { size: [0.0, 4.0] }.lerp(&Shape::Square { size: [2.0, 0.0] }, 0.5),
+			Shape::Square { size: [1.0, 2.0] }
+		);
+		assert_eq!(Shape::Circle(0.0).lerp(&Shape::Hidden, 0.4), Shape::Circle(0.0));
+		assert_eq!(Shape::Circle(0.0).lerp(&Shape::Hidden, 0.6), Shape::Hidden);
+	}
+
+	#[test]
+	fn color_spaces_keep_endpoints() {
+		let from = OklchColor(Color32::from_rgb(255, 0, 0));
+		let to = OklchColor(Color32::from_rgb(0, 0, 255));
+		assert_eq!(from.lerp(&to, 0.0), from);
+		assert_eq!(from.lerp(&to, 1.0), to);
+		assert_eq!(Some(1.0f32).lerp(&None, 0.4), Some(1.0));
+	}
+
+	#[test]
+	fn maps_follow_the_target_keys() {
+		let from = BTreeMap::from([("kept", 0.0f32), ("removed", 1.0)]);
+		let to = BTreeMap::from([("kept", 2.0f32), ("added", 3.0)]);
+		assert_eq!(from.lerp(&to, 0.5), BTreeMap::from([("kept", 1.0), ("added", 3.0)]));
+	}
+}
@@ -1,24 +1,40 @@
 //! # Pitaya Animation
 //! The core of pitayas fluid design language.
-//! This crate contains the Pitaya animation system that supports blending between two values in a certain amount of time with an optional ease curve.
+//! This crate contains the Pitaya animation system that supports blending between two values in a certain amount of time with an optional ease curve,
+//! or with spring physics that keep their momentum when the target changes.
+//! Composite motion can be built from keyframes with timelines.
+//! Custom types can be animated by deriving [Lerp](macro@Lerp).
 
+mod clock;
 pub mod config;
 mod easing;
 mod lerp;
 mod manager;
+mod spring;
+mod timeline;
 
-pub use crate::easing::Easing;
-pub use crate::lerp::{extend, Lerp};
-pub use crate::manager::AnimationManager;
+pub use crate::clock::Clock;
+pub use crate::easing::{Easing, Motion};
+pub use crate::lerp::{extend, snap, HsvColor, Lerp, LinearColor, OklabColor, OklchColor};
+pub use ptya_animation_derive::Lerp;
+pub use crate::manager::{AnimationManager, AnimationStats};
+pub use crate::spring::Spring;
+pub use crate::timeline::{Keyframes, Looping, Timeline, TimelineAnimation, TimelineImpl};
+use crate::config::{AnimationConfig, EasingConfig};
+use std::any::type_name;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
 use std::marker::PhantomData;
 
 use egui::{Id, Pos2, Rect};
 use parking_lot::Mutex;
 use std::sync::Arc;
 
+/// A typed handle to an animation in the [AnimationManager], animations can only be reached through these.
+/// Create it once and keep it around, the value type can then never be mixed up.
 pub struct AnimationRef<L> {
 	pub id: Id,
-	_d: PhantomData<L>,
+	_d: PhantomData<fn() -> L>,
 }
 
 impl<L> AnimationRef<L> {
@@ -28,11 +44,37 @@ impl<L> AnimationRef<L> {
 			_d: Default::default(),
 		}
 	}
+
+	/// Creates a handle for a child animation.
+	pub fn with(&self, child: impl Hash) -> AnimationRef<L> {
+		AnimationRef::new(self.id.with(child))
+	}
+}
+
+impl<L> Clone for AnimationRef<L> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<L> Copy for AnimationRef<L> {}
+
+impl<L> PartialEq for AnimationRef<L> {
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
+	}
+}
+
+impl<L> Debug for AnimationRef<L> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "AnimationRef<{}>({:?})", type_name::<L>(), self.id)
+	}
 }
 
 pub struct Animation<L: Lerp + Send + Sync> {
 	pub(crate) time: f64,
 	pub(crate) animation_time: f64,
+	pub(crate) easings: EasingConfig,
 	pub(crate) inner: AnimationImpl<L>,
 	pub(crate) link: Arc<Mutex<AnimationImpl<L>>>,
 }
@@ -56,9 +98,31 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 
 	/// If the to value is not the same as the parameter
 	/// it will wait until the animation is finished and then "redirect" the animation to the new state.
+	/// Spring animations redirect instantly and keep their momentum.
 	pub fn redirect_with_speed(&mut self, to: L, speed: f32) -> &mut Self {
 		if self.get_to() != &to {
-			self.when_done(|ani| ani.anchor_from().set_to(to).begin_with_speed(speed));
+			if self.inner.spring.is_some() {
+				self.retarget_with_speed(to, speed);
+			} else {
+				self.when_done(|ani| ani.anchor_from().set_to(to).begin_with_speed(speed));
+			}
+		}
+		self
+	}
+
+	pub fn retarget(&mut self, to: L) -> &mut Self {
+		self.retarget_with_speed(to, 1.0)
+	}
+
+	/// Starts moving towards a new target from the current value.
+	/// Springs keep their current speed and direction, values without a distance (see [Lerp::span_dot])
+	/// keep their velocity relative to the distance they travel instead. Easing animations start over.
+	pub fn retarget_with_speed(&mut self, to: L, speed: f32) -> &mut Self {
+		let velocity = self.get_velocity();
+		let (from, old_to) = (self.inner.from.clone(), self.inner.to.clone());
+		self.anchor_from().set_to(to).begin_with_speed(speed);
+		if self.inner.spring.is_some() {
+			self.inner.velocity = velocity * span_scale(&from, &old_to, &self.inner.from, &self.inner.to);
 		}
 		self
 	}
@@ -67,6 +131,7 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 	pub fn set_value(&mut self, value: L) -> &mut Self {
 		self.inner.start = 0.0;
 		self.inner.duration = 0.0;
+		self.inner.velocity = 0.0;
 		self.inner.from = value.clone();
 		self.inner.to = value;
 		self
@@ -74,18 +139,18 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 
 	/// Checks if the animation is currently moving
 	pub fn is_active(&self) -> bool {
-		let pos = self.get_pos();
-		pos > 0.0 && pos < 1.0
+		self.has_started() && !self.is_finished()
 	}
 
 	pub fn has_started(&self) -> bool {
-		let pos = self.get_pos();
-		pos > 0.0
+		self.inner.duration == 0.0 || self.time > self.inner.start
 	}
 
 	pub fn is_finished(&self) -> bool {
-		let pos = self.get_pos();
-		pos >= 1.0
+		match self.spring_state() {
+			Some((displacement, velocity)) => Spring::is_at_rest(displacement, velocity),
+			None => self.get_pos() >= 1.0,
+		}
 	}
 
 	pub fn when_done(&mut self, func: impl FnOnce(&mut Self)) {
@@ -94,23 +159,49 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 		}
 	}
 
-	/// Gets the current position of the animation
+	/// Gets the current position of the animation.
+	/// For springs this is the progress towards the target which may overshoot 1.0.
 	pub fn get_pos(&self) -> f64 {
-		if self.inner.duration == 0.0 {
+		if let Some((displacement, _)) = self.spring_state() {
+			1.0 + displacement
+		} else if self.inner.duration == 0.0 {
 			1.0
 		} else {
 			(self.time - self.inner.start) / self.inner.duration
 		}
 	}
 
+	/// Gets the current velocity in progress per second, this is always 0 for easing animations.
+	pub fn get_velocity(&self) -> f64 {
+		self.spring_state().map(|(_, velocity)| velocity).unwrap_or(0.0)
+	}
+
 	/// Gets the current value of the animation
 	pub fn get_value(&self) -> L {
+		if self.inner.spring.is_some() {
+			return self.inner.from.lerp(&self.inner.to, self.get_pos() as f32);
+		}
+
 		let time_t = self.get_pos();
 		let clamped_t = time_t.clamp(0.0, 1.0);
-		let eased_t = self.inner.easing.apply(clamped_t);
+		let eased_t = self.inner.easing.resolve(&self.easings).apply(clamped_t);
 		self.inner.from.lerp(&self.inner.to, eased_t as f32)
 	}
 
+	/// Solves the spring (if any) at the current time, returning the displacement from the target and velocity.
+	fn spring_state(&self) -> Option<(f64, f64)> {
+		let spring = self.inner.spring?;
+		if self.inner.duration == 0.0 {
+			return Some((0.0, 0.0));
+		}
+
+		// Springs do not have a duration, instead the speed scales time.
+		let scale = AnimationConfig::default_animation_speed() as f64 / self.inner.duration;
+		let elapsed = (self.time - self.inner.start).max(0.0) * scale;
+		let (displacement, velocity) = spring.solve(-1.0, self.inner.velocity / scale, elapsed);
+		Some((displacement, velocity * scale))
+	}
+
 	/// Starts a new animation to a new target.
 	pub fn begin(&mut self) {
 		self.begin_with_speed(1.0);
@@ -119,6 +210,14 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 	pub fn begin_with_speed(&mut self, speed: f32) {
 		self.inner.start = self.time;
 		self.inner.duration = speed as f64 * self.animation_time;
+		self.inner.velocity = 0.0;
+	}
+
+	/// Sets the velocity of a spring animation in progress per second, useful to fling a value after a drag.
+	/// Call this after beginning the animation.
+	pub fn set_velocity(&mut self, velocity: f64) -> &mut Self {
+		self.inner.velocity = velocity;
+		self
 	}
 
 	/// Overwrites the current source value
@@ -133,9 +232,16 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 		self
 	}
 
-	/// Overwrites the current easing
+	/// Overwrites the current easing, this also turns off spring physics.
 	pub fn set_easing(&mut self, easing: Easing) -> &mut Self {
 		self.inner.easing = easing;
+		self.inner.spring = None;
+		self
+	}
+
+	/// Uses spring physics instead of an easing curve.
+	pub fn set_spring(&mut self, spring: Spring) -> &mut Self {
+		self.inner.spring = Some(spring);
 		self
 	}
 
@@ -148,6 +254,16 @@ impl<L: Lerp + Send + Sync> Animation<L> {
 	}
 }
 
+/// Converts a velocity in progress per second over one span into progress per second over another span.
+fn span_scale<L: Lerp>(from: &L, to: &L, new_from: &L, new_to: &L) -> f64 {
+	match (L::span_dot(from, to, new_from, new_to), L::span_dot(new_from, new_to, new_from, new_to)) {
+		(Some(along), Some(length)) if length > f64::EPSILON => along / length,
+		// The new span has no length, there is nothing left to move through.
+		(Some(_), Some(_)) => 0.0,
+		_ => 1.0,
+	}
+}
+
 impl<L: Lerp + Send + Sync> Drop for Animation<L> {
 	fn drop(&mut self) {
 		*self.link.lock() = self.inner.clone();
@@ -166,9 +282,13 @@ pub struct AnimationImpl<L: Lerp + Send + Sync> {
 	pub from: L,
 	pub to: L,
 	pub easing: Easing,
+	/// Uses spring physics instead of the easing when set.
+	pub spring: Option<Spring>,
 	// seconds time
 	pub(crate) start: f64,
 	pub(crate) duration: f64,
+	// progress per second at start, only used by springs
+	pub(crate) velocity: f64,
 }
 
 impl<L: Lerp + Send + Sync> AnimationImpl<L> {
@@ -177,8 +297,17 @@ impl<L: Lerp + Send + Sync> AnimationImpl<L> {
 			from,
 			to,
 			easing,
+			spring: None,
 			start: 0.0,
 			duration: 0.0,
+			velocity: 0.0,
+		}
+	}
+
+	pub fn spring(value: L, spring: Spring) -> AnimationImpl<L> {
+		AnimationImpl {
+			spring: Some(spring),
+			..AnimationImpl::simple(value)
 		}
 	}
 
@@ -187,8 +316,10 @@ impl<L: Lerp + Send + Sync> AnimationImpl<L> {
 			from: value.clone(),
 			to: value,
 			easing: Easing::EaseInOut,
+			spring: None,
 			start: 0.0,
 			duration: 0.0,
+			velocity: 0.0,
 		}
 	}
 }
@@ -199,8 +330,10 @@ impl AnimationImpl<Rect> {
 			from: Rect::from_min_max(Pos2::ZERO, Pos2::ZERO),
 			to: Rect::from_min_max(Pos2::ZERO, Pos2::ZERO),
 			easing: Easing::EaseInOut,
+			spring: None,
 			start: 0.0,
 			duration: 0.0,
+			velocity: 0.0,
 		}
 	}
 }
@@ -1,11 +1,13 @@
+use crate::clock::Clock;
 use crate::config::AnimationConfig;
 use crate::lerp::Lerp;
-use crate::{Animation, AnimationImpl};
+use crate::timeline::{Timeline, TimelineAnimation, TimelineImpl};
+use crate::{Animation, AnimationImpl, AnimationRef};
 use ahash::AHashMap;
 use egui::{Context, Id};
 use log::{info, trace};
 use parking_lot::Mutex;
-use std::any::{type_name, Any};
+use std::any::{type_name, Any, TypeId};
 use std::sync::Arc;
 
 pub struct AnimationManager {
@@ -14,6 +16,11 @@ pub struct AnimationManager {
 
 impl AnimationManager {
     pub async fn new(config: AnimationConfig) -> AnimationManager {
+        Self::with_clock(config, Clock::RealTime)
+    }
+
+    /// Creates a manager driven by a specific clock, use [Clock::Manual] to step animations by hand.
+    pub fn with_clock(config: AnimationConfig, clock: Clock) -> AnimationManager {
         info!("Created animation manager");
 
         AnimationManager {
@@ -22,52 +29,129 @@ impl AnimationManager {
                 animations: Default::default(),
                 any_active: false,
                 time: 0.0,
+                frame: 0,
+                clock,
+                recording: None,
             }),
         }
     }
 
-    pub fn get<L: Lerp + Send + Sync + Default>(&self, id: Id) -> Animation<L> {
-        self.get_or(id, AnimationImpl::default)
+    /// Gets the animation behind a typed handle.
+    pub fn get_ref<L: Lerp + Send + Sync + Default>(&self, handle: AnimationRef<L>) -> Animation<L> {
+        self.get_ref_or(handle, AnimationImpl::default)
     }
 
-    pub fn get_or<L: Lerp + Send + Sync>(
+    /// Gets the animation behind a typed handle, creating it with `default` if it does not exist yet.
+    /// Animations are namespaced by their type, so the same id can be used for values of different types.
+    pub fn get_ref_or<L: Lerp + Send + Sync>(
         &self,
-        id: Id,
+        handle: AnimationRef<L>,
         default: impl FnOnce() -> AnimationImpl<L>,
     ) -> Animation<L> {
         let mut inner = self.inner.lock();
-        let arc = inner.animations.entry(id).or_insert_with(|| {
-            let inner = default();
-            trace!("Added animation <{}> to {id:?}", type_name::<L>());
-            Box::new(Arc::new(Mutex::new(inner)))
-        });
-        let any = &(**arc);
-        let link = any
-            .downcast_ref::<Arc<Mutex<AnimationImpl<L>>>>()
-            .unwrap_or_else(|| panic!("Wrong type <{}> for animation at {id:?}", type_name::<L>()))
-            .clone();
+        let link = inner.link(handle.id, "animation", default);
 
         let animation = (link.lock()).clone();
         let animation = Animation {
             time: inner.time,
             animation_time: inner.config.animation_speed as f64,
+            easings: inner.config.easing,
             inner: animation,
             link,
         };
 
-        if !inner.any_active {
-            inner.any_active = animation.is_active();
-        }
-
+        inner.mark_active::<AnimationImpl<L>>(handle.id, animation.is_active());
         animation
     }
 
-    pub fn tick(&self, ctx: &Context) {
+    /// Gets a timeline animation, the timeline is created with `default` if it does not exist yet.
+    pub fn get_timeline<L: Lerp + Send + Sync>(
+        &self,
+        id: Id,
+        default: impl FnOnce() -> Timeline<L>,
+    ) -> TimelineAnimation<L> {
         let mut inner = self.inner.lock();
-        inner.time = ctx.input().time;
-        if inner.any_active {
+        let link = inner.link(id, "timeline", || TimelineImpl::new(default()));
+
+        let timeline = (link.lock()).clone();
+        let timeline = TimelineAnimation {
+            time: inner.time,
+            animation_time: inner.config.animation_speed as f64,
+            easings: inner.config.easing,
+            inner: timeline,
+            link,
+        };
+
+        inner.mark_active::<TimelineImpl<L>>(id, timeline.is_active());
+        timeline
+    }
+
+    pub fn tick(&self, ctx: &Context) {
+        if self.update(ctx.input().time) {
             ctx.request_repaint();
-            inner.any_active = false;
+        }
+    }
+
+    /// Starts a new frame, `real_time` is only used by [Clock::RealTime].
+    /// Returns if any animation was moving in the last frame.
+    pub fn update(&self, real_time: f64) -> bool {
+        let mut inner = self.inner.lock();
+        inner.time = inner.clock.tick(real_time);
+        inner.frame += 1;
+        let time = inner.time;
+        if let Some(recording) = &mut inner.recording {
+            recording.push(time);
+        }
+        inner.collect_stale();
+        std::mem::replace(&mut inner.any_active, false)
+    }
+
+    /// Moves a [Clock::Manual] forward and starts a new frame.
+    pub fn advance(&self, seconds: f64) {
+        self.inner.lock().clock.advance(seconds);
+        self.update(0.0);
+    }
+
+    pub fn set_clock(&self, clock: Clock) {
+        self.inner.lock().clock = clock;
+    }
+
+    /// The time of the current frame, use this instead of the ui time so things stay in sync with the animations.
+    pub fn time(&self) -> f64 {
+        self.inner.lock().time
+    }
+
+    /// Checks if components should fade instead of moving or resizing things.
+    pub fn reduced_motion(&self) -> bool {
+        self.inner.lock().config.reduced_motion
+    }
+
+    /// Switches reduced motion at runtime, animations that are already running finish as they are.
+    pub fn set_reduced_motion(&self, reduced_motion: bool) {
+        info!("Reduced motion {}", if reduced_motion { "on" } else { "off" });
+        self.inner.lock().config.reduced_motion = reduced_motion;
+    }
+
+    /// Starts recording the time of every frame, these can be played back with [Clock::Replay].
+    pub fn start_recording(&self) {
+        self.inner.lock().recording = Some(vec![]);
+    }
+
+    pub fn stop_recording(&self) -> Vec<f64> {
+        self.inner.lock().recording.take().unwrap_or_default()
+    }
+
+    /// Gets the amount of animations currently tracked, for debugging.
+    pub fn stats(&self) -> AnimationStats {
+        let inner = self.inner.lock();
+        AnimationStats {
+            live: inner.animations.len(),
+            active: inner
+                .animations
+                .values()
+                .filter(|entry| entry.active && inner.frame - entry.last_frame <= 1)
+                .count(),
+            frame: inner.frame,
         }
     }
 
@@ -80,9 +164,113 @@ impl AnimationManager {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct AnimationStats {
+    /// Animations that are currently kept in memory.
+    pub live: usize,
+    /// Animations that moved in the last frame.
+    pub active: usize,
+    pub frame: u64,
+}
+
 struct AnimationManagerInner {
     config: AnimationConfig,
-    animations: AHashMap<Id, Box<dyn Any + Send + Sync>>,
+    // Keyed by the type of the animation too, so ids can not collide across types.
+    animations: AHashMap<(Id, TypeId), AnimationEntry>,
     any_active: bool,
     time: f64,
+    frame: u64,
+    clock: Clock,
+    recording: Option<Vec<f64>>,
+}
+
+impl AnimationManagerInner {
+    fn link<T: Any + Send + Sync>(
+        &mut self,
+        id: Id,
+        kind: &str,
+        default: impl FnOnce() -> T,
+    ) -> Arc<Mutex<T>> {
+        let (frame, time) = (self.frame, self.time);
+        let entry = self.animations.entry((id, TypeId::of::<T>())).or_insert_with(|| {
+            trace!("Added {kind} <{}> to {id:?}", type_name::<T>());
+            AnimationEntry {
+                value: Box::new(Arc::new(Mutex::new(default()))),
+                last_access: time,
+                last_frame: frame,
+                active: false,
+            }
+        });
+        entry.last_access = time;
+        entry.last_frame = frame;
+        entry
+            .value
+            .downcast_ref::<Arc<Mutex<T>>>()
+            .expect("Animations are keyed by type")
+            .clone()
+    }
+
+    fn mark_active<T: Any>(&mut self, id: Id, active: bool) {
+        if let Some(entry) = self.animations.get_mut(&(id, TypeId::of::<T>())) {
+            entry.active = active;
+        }
+        self.any_active |= active;
+    }
+
+    /// Removes animations that have not been used for a while, they would otherwise live forever.
+    /// This goes by the clock, so it does not depend on the frame rate.
+    fn collect_stale(&mut self) {
+        let time = self.time;
+        let stale_seconds = self.config.stale_seconds;
+        if stale_seconds <= 0.0 {
+            return;
+        }
+
+        let before = self.animations.len();
+        self.animations
+            .retain(|_, entry| time - entry.last_access <= stale_seconds);
+        let removed = before - self.animations.len();
+        if removed > 0 {
+            trace!("Removed {removed} stale animations");
+        }
+    }
+}
+
+struct AnimationEntry {
+    value: Box<dyn Any + Send + Sync>,
+    // The clock time of the frame it was last used in.
+    last_access: f64,
+    last_frame: u64,
+    // If the animation was moving when it was last accessed.
+    active: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_animations_are_removed() {
+        let config = AnimationConfig {
+            stale_seconds: 1.0,
+            ..AnimationConfig::default()
+        };
+        let manager = AnimationManager::with_clock(config, Clock::manual());
+        let used = AnimationRef::<f32>::new(Id::new("used"));
+        let unused = AnimationRef::<f32>::new(Id::new("unused"));
+        manager.get_ref(unused);
+
+        // A few long frames age animations as much as many short ones.
+        for _ in 0..3 {
+            manager.get_ref(used);
+            manager.advance(0.6);
+        }
+        assert_eq!(manager.stats().live, 1);
+        manager.get_ref(used).set_value(1.0);
+
+        manager.advance(0.9);
+        assert_eq!(manager.get_ref(used).get_value(), 1.0);
+        manager.advance(1.5);
+        assert_eq!(manager.stats().live, 0);
+    }
 }
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+/// How close to the target (in progress) the spring needs to be before it is considered at rest.
+const REST_THRESHOLD: f64 = 0.001;
+
+/// A damped spring, used by animations that should keep their momentum when their target changes.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Spring {
+	pub stiffness: f32,
+	pub damping: f32,
+	pub mass: f32,
+}
+
+impl Spring {
+	/// Settles fast without overshooting, a good fit for layout changes.
+	pub const SNAPPY: Spring = Spring::new(400.0, 40.0, 1.0);
+	/// Overshoots and bounces back a little.
+	pub const BOUNCY: Spring = Spring::new(300.0, 15.0, 1.0);
+	/// A slow and soft spring.
+	pub const GENTLE: Spring = Spring::new(120.0, 22.0, 1.0);
+
+	pub const fn new(stiffness: f32, damping: f32, mass: f32) -> Spring {
+		Spring {
+			stiffness,
+			damping,
+			mass,
+		}
+	}
+
+	/// Solves the spring for a starting displacement from the target and a starting velocity.
+	/// Returns the displacement and velocity after `t` seconds.
+	pub fn solve(&self, x0: f64, v0: f64, t: f64) -> (f64, f64) {
+		let k = self.stiffness.max(f32::EPSILON) as f64;
+		let c = self.damping.max(0.0) as f64;
+		let m = self.mass.max(f32::EPSILON) as f64;
+
+		let omega = (k / m).sqrt();
+		let zeta = c / (2.0 * (k * m).sqrt());
+
+		if (zeta - 1.0).abs() < 1e-6 {
+			// Critically damped
+			let b = v0 + omega * x0;
+			let decay = (-omega * t).exp();
+			let x = decay * (x0 + b * t);
+			(x, -omega * x + decay * b)
+		} else if zeta < 1.0 {
+			// Under damped, this is the one that bounces.
+			let a = -zeta * omega;
+			let w = omega * (1.0 - zeta * zeta).sqrt();
+			let b = (v0 - a * x0) / w;
+			let decay = (a * t).exp();
+			let (sin, cos) = (w * t).sin_cos();
+			let x = decay * (x0 * cos + b * sin);
+			(x, a * x + decay * (-x0 * w * sin + b * w * cos))
+		} else {
+			// Over damped
+			let root = (zeta * zeta - 1.0).sqrt();
+			let r1 = -omega * (zeta - root);
+			let r2 = -omega * (zeta + root);
+			let c1 = (v0 - r2 * x0) / (r1 - r2);
+			let c2 = x0 - c1;
+			let (e1, e2) = ((r1 * t).exp(), (r2 * t).exp());
+			(c1 * e1 + c2 * e2, c1 * r1 * e1 + c2 * r2 * e2)
+		}
+	}
+
+	/// Checks if the spring has come to rest.
+	pub fn is_at_rest(displacement: f64, velocity: f64) -> bool {
+		displacement.abs() < REST_THRESHOLD && velocity.abs() < REST_THRESHOLD
+	}
+}
+
+impl Default for Spring {
+	fn default() -> Self {
+		Spring::SNAPPY
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::config::AnimationConfig;
+	use crate::{AnimationImpl, AnimationManager, AnimationRef, Clock, Spring};
+	use egui::Id;
+
+	const STEP: f64 = 1.0 / 60.0;
+	/// Short enough that the speed barely changes in between.
+	const NUDGE: f64 = 1e-4;
+
+	fn manager() -> AnimationManager {
+		AnimationManager::with_clock(AnimationConfig::default(), Clock::manual())
+	}
+
+	/// The value and how fast it moves in units per second.
+	fn state(manager: &AnimationManager, id: AnimationRef<f32>) -> (f32, f64) {
+		let animation = manager.get_ref(id);
+		let span = (animation.get_to() - animation.get_from()) as f64;
+		(animation.get_value(), animation.get_velocity() * span)
+	}
+
+	/// Starts a spring from 0 to 1 and lets it move for a few frames.
+	fn moving(manager: &AnimationManager, id: AnimationRef<f32>) -> (f32, f64) {
+		manager
+			.get_ref_or(id, || AnimationImpl::spring(0.0f32, Spring::SNAPPY))
+			.set_to(1.0)
+			.begin();
+		for _ in 0..3 {
+			manager.advance(STEP);
+		}
+		state(manager, id)
+	}
+
+	#[test]
+	fn springs_come_to_rest() {
+		assert!(Spring::is_at_rest(0.0005, -0.0005));
+		assert!(!Spring::is_at_rest(0.01, 0.0));
+		assert!(!Spring::is_at_rest(0.0, 0.01));
+
+		let manager = manager();
+		let id = AnimationRef::<f32>::new(Id::new("rest"));
+		moving(&manager, id);
+		assert!(manager.get_ref(id).is_active());
+		manager.advance(5.0);
+		let animation = manager.get_ref(id);
+		assert!(animation.is_finished());
+		assert!((animation.get_value() - 1.0).abs() < 0.001);
+	}
+
+	#[test]
+	fn retargeting_keeps_the_value_and_speed() {
+		let manager = manager();
+		let id = AnimationRef::<f32>::new(Id::new("retarget"));
+		let (value, speed) = moving(&manager, id);
+		assert!(speed > 0.0);
+
+		manager.get_ref(id).retarget(4.0);
+		let (retargeted, retargeted_speed) = state(&manager, id);
+		assert_eq!(retargeted, value);
+		assert!((retargeted_speed - speed).abs() < 1e-3, "{retargeted_speed} != {speed}");
+
+		// The value also moves on smoothly in the next frame.
+		manager.advance(NUDGE);
+		let moved = (manager.get_ref(id).get_value() - value) as f64 / NUDGE;
+		assert!((moved - speed).abs() < speed * 0.05, "{moved} != {speed}");
+	}
+
+	#[test]
+	fn retargeting_backwards_keeps_the_momentum() {
+		let manager = manager();
+		let id = AnimationRef::<f32>::new(Id::new("backwards"));
+		let (value, speed) = moving(&manager, id);
+
+		manager.get_ref(id).retarget(-1.0);
+		let (_, retargeted_speed) = state(&manager, id);
+		assert!((retargeted_speed - speed).abs() < 1e-3, "{retargeted_speed} != {speed}");
+
+		// It keeps going up for a moment before it turns around.
+		manager.advance(NUDGE);
+		assert!(manager.get_ref(id).get_value() > value);
+		manager.advance(1.0);
+		assert!(manager.get_ref(id).get_value() < value);
+	}
+}
@@ -0,0 +1,401 @@
+use crate::config::EasingConfig;
+use crate::{Easing, Lerp};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// A value moving through multiple keyframes.
+/// Durations are in multiples of the configured animation speed, like [Animation::begin_with_speed](crate::Animation::begin_with_speed).
+#[derive(Clone)]
+pub struct Keyframes<L: Lerp + Send + Sync> {
+	start: L,
+	// (end time, value, easing used to get to the value)
+	frames: Vec<(f64, L, Easing)>,
+}
+
+impl<L: Lerp + Send + Sync> Keyframes<L> {
+	pub fn new(start: L) -> Keyframes<L> {
+		Keyframes {
+			start,
+			frames: vec![],
+		}
+	}
+
+	/// Adds a keyframe that is reached `duration` after the previous one.
+	pub fn then(mut self, duration: f64, value: L, easing: Easing) -> Self {
+		let time = self.duration() + duration.max(0.0);
+		self.frames.push((time, value, easing));
+		self
+	}
+
+	/// Holds the current value for `duration`.
+	pub fn hold(self, duration: f64) -> Self {
+		let value = self.end().clone();
+		self.then(duration, value, Easing::Linear)
+	}
+
+	pub fn duration(&self) -> f64 {
+		self.frames.last().map(|(time, _, _)| *time).unwrap_or(0.0)
+	}
+
+	pub fn end(&self) -> &L {
+		self.frames.last().map(|(_, value, _)| value).unwrap_or(&self.start)
+	}
+
+	/// Gets the value at a certain time, before the first keyframe starts this is the start value.
+	pub fn sample(&self, time: f64, easings: &EasingConfig) -> L {
+		if time <= 0.0 {
+			return self.start.clone();
+		}
+
+		let mut from_time = 0.0;
+		let mut from = &self.start;
+		for (to_time, to, easing) in &self.frames {
+			if time < *to_time {
+				let t = (time - from_time) / (to_time - from_time);
+				let t = easing.resolve(easings).apply(t);
+				return from.lerp(to, t as f32);
+			}
+			from_time = *to_time;
+			from = to;
+		}
+		from.clone()
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Looping {
+	Once,
+	/// Plays the timeline a certain amount of times, `Repeat(0)` plays it once like [Looping::Once].
+	Repeat(u32),
+	Forever,
+	/// Plays the timeline forwards and then backwards, forever.
+	PingPong,
+}
+
+/// A group of [Keyframes] tracks with their own start delay.
+/// Tracks are addressed by the order they were added in.
+#[derive(Clone)]
+pub struct Timeline<L: Lerp + Send + Sync> {
+	tracks: Vec<(f64, Keyframes<L>)>,
+	looping: Looping,
+}
+
+impl<L: Lerp + Send + Sync> Timeline<L> {
+	pub fn new() -> Timeline<L> {
+		Timeline {
+			tracks: vec![],
+			looping: Looping::Once,
+		}
+	}
+
+	/// Plays every track at the same time.
+	pub fn parallel(tracks: impl IntoIterator<Item = Keyframes<L>>) -> Timeline<L> {
+		Self::stagger(tracks, 0.0)
+	}
+
+	/// Plays the tracks one after another.
+	pub fn sequence(tracks: impl IntoIterator<Item = Keyframes<L>>) -> Timeline<L> {
+		let mut timeline = Timeline::new();
+		for track in tracks {
+			let delay = timeline.duration();
+			timeline.tracks.push((delay, track));
+		}
+		timeline
+	}
+
+	/// Starts every track `delay` after the previous one.
+	pub fn stagger(tracks: impl IntoIterator<Item = Keyframes<L>>, delay: f64) -> Timeline<L> {
+		Timeline {
+			tracks: tracks
+				.into_iter()
+				.enumerate()
+				.map(|(i, track)| (i as f64 * delay, track))
+				.collect(),
+			looping: Looping::Once,
+		}
+	}
+
+	/// Adds a track that starts `delay` after the timeline starts.
+	pub fn track(mut self, delay: f64, keyframes: Keyframes<L>) -> Self {
+		self.tracks.push((delay, keyframes));
+		self
+	}
+
+	/// Plays the tracks of another timeline once this one is done.
+	pub fn then(mut self, other: Timeline<L>) -> Self {
+		let offset = self.duration();
+		self.tracks
+			.extend(other.tracks.into_iter().map(|(delay, track)| (delay + offset, track)));
+		self
+	}
+
+	/// Plays the tracks of another timeline alongside this one.
+	pub fn and(mut self, other: Timeline<L>) -> Self {
+		self.tracks.extend(other.tracks);
+		self
+	}
+
+	pub fn looping(mut self, looping: Looping) -> Self {
+		self.looping = looping;
+		self
+	}
+
+	pub fn len(&self) -> usize {
+		self.tracks.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.tracks.is_empty()
+	}
+
+	/// The length of a single play through.
+	pub fn duration(&self) -> f64 {
+		self.tracks
+			.iter()
+			.map(|(delay, track)| delay + track.duration())
+			.fold(0.0, f64::max)
+	}
+
+	/// The total length including loops, [None] if it loops forever.
+	pub fn total_duration(&self) -> Option<f64> {
+		match self.looping {
+			Looping::Once => Some(self.duration()),
+			Looping::Repeat(times) => Some(self.duration() * times.max(1) as f64),
+			Looping::Forever | Looping::PingPong => None,
+		}
+	}
+
+	/// Maps the time since the start to the time within a single play through.
+	fn local_time(&self, elapsed: f64) -> f64 {
+		let duration = self.duration();
+		if duration == 0.0 {
+			return 0.0;
+		}
+
+		match self.looping {
+			Looping::Once => elapsed.min(duration),
+			Looping::Repeat(times) if elapsed >= duration * times.max(1) as f64 => duration,
+			Looping::Repeat(_) | Looping::Forever => elapsed % duration,
+			Looping::PingPong => {
+				let time = elapsed % (duration * 2.0);
+				if time > duration {
+					duration * 2.0 - time
+				} else {
+					time
+				}
+			}
+		}
+	}
+
+	/// Gets the value of a track at a time since the start of the timeline.
+	pub fn sample(&self, track: usize, elapsed: f64, easings: &EasingConfig) -> Option<L> {
+		let time = self.local_time(elapsed);
+		self.tracks
+			.get(track)
+			.map(|(delay, keyframes)| keyframes.sample(time - delay, easings))
+	}
+}
+
+impl<L: Lerp + Send + Sync> Default for Timeline<L> {
+	fn default() -> Self {
+		Timeline::new()
+	}
+}
+
+/// A [Timeline] that is being played by the [AnimationManager](crate::AnimationManager).
+pub struct TimelineAnimation<L: Lerp + Send + Sync> {
+	pub(crate) time: f64,
+	pub(crate) animation_time: f64,
+	pub(crate) easings: EasingConfig,
+	pub(crate) inner: TimelineImpl<L>,
+	pub(crate) link: Arc<Mutex<TimelineImpl<L>>>,
+}
+
+impl<L: Lerp + Send + Sync> TimelineAnimation<L> {
+	/// Starts playing the timeline from the beginning.
+	pub fn play(&mut self) -> &mut Self {
+		self.inner.start = Some(self.time);
+		self.inner.completed = false;
+		self
+	}
+
+	/// Stops the timeline, tracks will return their starting value.
+	pub fn stop(&mut self) -> &mut Self {
+		self.inner.start = None;
+		self.inner.completed = false;
+		self
+	}
+
+	/// Replaces the timeline, this does not restart it.
+	pub fn set_timeline(&mut self, timeline: Timeline<L>) -> &mut Self {
+		self.inner.timeline = timeline;
+		self
+	}
+
+	pub fn timeline(&self) -> &Timeline<L> {
+		&self.inner.timeline
+	}
+
+	/// The time since the timeline started in multiples of the animation speed.
+	fn elapsed(&self) -> Option<f64> {
+		self.inner.start.map(|start| {
+			if self.animation_time == 0.0 {
+				f64::INFINITY
+			} else {
+				(self.time - start) / self.animation_time
+			}
+		})
+	}
+
+	pub fn is_playing(&self) -> bool {
+		self.inner.start.is_some()
+	}
+
+	pub fn is_active(&self) -> bool {
+		self.is_playing() && !self.is_finished()
+	}
+
+	/// Checks if the timeline has played all of its loops, this is never true for endless loops.
+	pub fn is_finished(&self) -> bool {
+		match (self.elapsed(), self.inner.timeline.total_duration()) {
+			(Some(elapsed), Some(duration)) => elapsed >= duration,
+			_ => false,
+		}
+	}
+
+	/// Runs `func` once when the timeline finishes.
+	pub fn on_complete(&mut self, func: impl FnOnce(&mut Self)) -> &mut Self {
+		if !self.inner.completed && self.is_finished() {
+			self.inner.completed = true;
+			func(self);
+		}
+		self
+	}
+
+	/// Gets the current value of a track, [None] if the track does not exist.
+	pub fn get_value(&self, track: usize) -> Option<L> {
+		let elapsed = match self.elapsed() {
+			Some(elapsed) if elapsed.is_finite() => elapsed,
+			Some(_) => self.inner.timeline.total_duration().unwrap_or(0.0),
+			None => 0.0,
+		};
+		self.inner.timeline.sample(track, elapsed, &self.easings)
+	}
+
+	/// Gets the current value of every track.
+	pub fn get_values(&self) -> Vec<L> {
+		(0..self.inner.timeline.len())
+			.filter_map(|track| self.get_value(track))
+			.collect()
+	}
+}
+
+impl<L: Lerp + Send + Sync> Drop for TimelineAnimation<L> {
+	fn drop(&mut self) {
+		*self.link.lock() = self.inner.clone();
+	}
+}
+
+#[derive(Clone)]
+pub struct TimelineImpl<L: Lerp + Send + Sync> {
+	pub timeline: Timeline<L>,
+	pub(crate) start: Option<f64>,
+	pub(crate) completed: bool,
+}
+
+impl<L: Lerp + Send + Sync> TimelineImpl<L> {
+	pub fn new(timeline: Timeline<L>) -> TimelineImpl<L> {
+		TimelineImpl {
+			timeline,
+			start: None,
+			completed: false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::AnimationConfig;
+	use crate::{AnimationManager, Clock};
+	use egui::Id;
+
+	fn ramp(to: f32) -> Keyframes<f32> {
+		Keyframes::new(0.0).then(1.0, to, Easing::Linear)
+	}
+
+	#[test]
+	fn keyframes_are_sampled_at_their_boundaries() {
+		let easings = EasingConfig::default();
+		let keyframes = ramp(1.0).hold(1.0).then(2.0, 3.0, Easing::Linear);
+		assert_eq!(keyframes.duration(), 4.0);
+		assert_eq!(keyframes.sample(-1.0, &easings), 0.0);
+		assert_eq!(keyframes.sample(0.0, &easings), 0.0);
+		assert_eq!(keyframes.sample(0.5, &easings), 0.5);
+		assert_eq!(keyframes.sample(1.0, &easings), 1.0);
+		assert_eq!(keyframes.sample(2.0, &easings), 1.0);
+		assert_eq!(keyframes.sample(3.0, &easings), 2.0);
+		assert_eq!(keyframes.sample(4.0, &easings), 3.0);
+		assert_eq!(keyframes.sample(10.0, &easings), 3.0);
+		assert_eq!(Keyframes::new(5.0f32).sample(1.0, &easings), 5.0);
+	}
+
+	#[test]
+	fn staggered_tracks_start_after_each_other() {
+		let easings = EasingConfig::default();
+		let timeline = Timeline::stagger([ramp(1.0), ramp(1.0), ramp(1.0)], 0.5);
+		assert_eq!(timeline.duration(), 2.0);
+		let values = |elapsed| {
+			(0..3)
+				.map(|track| timeline.sample(track, elapsed, &easings).unwrap())
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(values(0.5), [0.5, 0.0, 0.0]);
+		assert_eq!(values(1.0), [1.0, 0.5, 0.0]);
+		assert_eq!(values(2.0), [1.0, 1.0, 1.0]);
+		assert_eq!(timeline.sample(3, 1.0, &easings), None);
+
+		let sequence = Timeline::sequence([ramp(1.0), ramp(2.0)]);
+		assert_eq!(sequence.duration(), 2.0);
+		assert_eq!(sequence.sample(1, 1.5, &easings), Some(1.0));
+	}
+
+	#[test]
+	fn timelines_loop() {
+		let easings = EasingConfig::default();
+		let timeline = Timeline::parallel([ramp(1.0)]).looping(Looping::Repeat(2));
+		assert_eq!(timeline.total_duration(), Some(2.0));
+		assert_eq!(timeline.sample(0, 1.25, &easings), Some(0.25));
+		assert_eq!(timeline.sample(0, 5.0, &easings), Some(1.0));
+
+		let once = Timeline::parallel([ramp(1.0)]).looping(Looping::Repeat(0));
+		assert_eq!(once.total_duration(), Some(1.0));
+		assert_eq!(once.sample(0, 0.5, &easings), Some(0.5));
+
+		let ping_pong = Timeline::parallel([ramp(1.0)]).looping(Looping::PingPong);
+		assert_eq!(ping_pong.total_duration(), None);
+		assert_eq!(ping_pong.sample(0, 1.25, &easings), Some(0.75));
+		assert_eq!(ping_pong.sample(0, 2.25, &easings), Some(0.25));
+	}
+
+	#[test]
+	fn on_complete_runs_once() {
+		let manager = AnimationManager::with_clock(AnimationConfig::default(), Clock::manual());
+		let speed = AnimationConfig::default_animation_speed() as f64;
+		let id = Id::new("timeline");
+		let timeline = || Timeline::parallel([ramp(1.0)]).looping(Looping::Repeat(2));
+		// Counts how often on_complete ran.
+		let frame = |seconds| {
+			manager.advance(seconds);
+			let mut completed = 0;
+			manager.get_timeline(id, timeline).on_complete(|_| completed += 1);
+			completed
+		};
+
+		manager.get_timeline(id, timeline).play();
+		assert_eq!(frame(speed * 1.5) + frame(speed * 0.4), 0);
+		assert_eq!(frame(speed * 0.2), 1);
+		assert_eq!(frame(speed), 0);
+		assert!(manager.get_timeline(id, timeline).is_finished());
+	}
+}
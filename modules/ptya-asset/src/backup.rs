@@ -0,0 +1,464 @@
+use crate::{AssetManager, Location};
+use anyways::audit::Audit;
+use anyways::ext::AuditExt;
+use anyways::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The archive format version, bump this when the layout changes.
+pub const BACKUP_VERSION: u32 = 1;
+/// The directory inside [Location::Data] where apps keep their data, one folder per app id.
+pub const APP_DATA_DIR: &str = "apps";
+
+const MANIFEST_PATH: &str = "manifest.json";
+const CONFIG_PREFIX: &str = "config";
+const DATA_PREFIX: &str = "data";
+
+/// Describes the contents of a profile backup. This is the first entry of every archive.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupManifest {
+	pub version: u32,
+	/// Unix time in seconds of when the backup was made.
+	pub created: u64,
+	/// The apps whose data is included in the backup.
+	pub apps: Vec<String>,
+	/// Every file in the archive and the sha256 of its contents.
+	pub files: BTreeMap<String, String>,
+	/// A sha256 over all file entries, catches a manifest that does not belong to its files.
+	pub checksum: String,
+}
+
+impl BackupManifest {
+	fn compute_checksum(files: &BTreeMap<String, String>) -> String {
+		let mut hasher = Sha256::new();
+		for (path, hash) in files {
+			hasher.update(path.as_bytes());
+			hasher.update([0]);
+			hasher.update(hash.as_bytes());
+			hasher.update(b"\n");
+		}
+		format!("{:x}", hasher.finalize())
+	}
+
+	fn validate(&self) -> Result<()> {
+		if self.version > BACKUP_VERSION {
+			return Err(Audit::new(format!(
+				"Backup version {} is newer than the supported version {BACKUP_VERSION}",
+				self.version
+			)));
+		}
+
+		if Self::compute_checksum(&self.files) != self.checksum {
+			return Err(Audit::new("Backup manifest checksum does not match"));
+		}
+
+		for path in self.files.keys() {
+			if !is_safe_entry(path) {
+				return Err(Audit::new(format!("Backup contains an invalid path \"{path}\"")));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl AssetManager {
+	/// Exports the [Location::Config] and [Location::Data] locations into a single archive at `target`.
+	/// App data is only included for the apps listed in `apps`.
+	pub async fn export_profile<P: AsRef<Path>>(
+		&self,
+		target: P,
+		apps: &[String],
+	) -> Result<BackupManifest> {
+		let asset = self.clone();
+		let target = target.as_ref().to_path_buf();
+		let apps = apps.to_vec();
+		tokio::task::spawn_blocking(move || asset.export_profile_blocking(&target, apps))
+			.await
+			.wrap_err("Export task panicked")?
+	}
+
+	/// Validates the archive at `source` and then swaps it in place of the current
+	/// [Location::Config] and [Location::Data] locations.
+	/// Nothing is touched if the archive is invalid.
+	pub async fn import_profile<P: AsRef<Path>>(&self, source: P) -> Result<BackupManifest> {
+		let asset = self.clone();
+		let source = source.as_ref().to_path_buf();
+		tokio::task::spawn_blocking(move || asset.import_profile_blocking(&source))
+			.await
+			.wrap_err("Import task panicked")?
+	}
+
+	fn export_profile_blocking(&self, target: &Path, apps: Vec<String>) -> Result<BackupManifest> {
+		let config = self.get_dir(Location::Config);
+		let data = self.get_dir(Location::Data);
+
+		let mut entries = Vec::new();
+		collect_files(config, CONFIG_PREFIX, &mut entries, &|_| true)
+			.wrap_err("Failed to collect config files")?;
+		collect_files(data, DATA_PREFIX, &mut entries, &|path| path != APP_DATA_DIR)
+			.wrap_err("Failed to collect data files")?;
+		for app in &apps {
+			let dir = data.join(APP_DATA_DIR).join(app);
+			if dir.is_dir() {
+				collect_files(&dir, &format!("{DATA_PREFIX}/{APP_DATA_DIR}/{app}"), &mut entries, &|_| true)
+					.wrap_err_with(|| format!("Failed to collect data of app {app}"))?;
+			} else {
+				warn!("App {app} has no data to export");
+			}
+		}
+
+		let mut files = BTreeMap::new();
+		let mut contents = Vec::with_capacity(entries.len());
+		for (name, path) in entries {
+			let data = fs::read(&path).wrap_err_with(|| format!("Failed to read {path:?}"))?;
+			files.insert(name.clone(), hash(&data));
+			contents.push((name, data));
+		}
+
+		let manifest = BackupManifest {
+			version: BACKUP_VERSION,
+			created: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|time| time.as_secs())
+				.unwrap_or(0),
+			apps,
+			checksum: BackupManifest::compute_checksum(&files),
+			files,
+		};
+
+		// Write to a temporary file first so a failed export never leaves a half written archive behind.
+		let temp = target.with_extension("partial");
+		{
+			let file = fs::File::create(&temp).wrap_err("Failed to create archive")?;
+			let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+			let manifest_data =
+				serde_json::to_vec_pretty(&manifest).wrap_err("Failed to serialize manifest")?;
+			append(&mut builder, MANIFEST_PATH, &manifest_data)?;
+			for (name, data) in &contents {
+				append(&mut builder, name, data)?;
+			}
+			builder
+				.into_inner()
+				.and_then(|encoder| encoder.finish())
+				.wrap_err("Failed to finish archive")?;
+		}
+		fs::rename(&temp, target).wrap_err("Failed to move archive into place")?;
+
+		info!("Exported profile with {} files to {target:?}", manifest.files.len());
+		Ok(manifest)
+	}
+
+	fn import_profile_blocking(&self, source: &Path) -> Result<BackupManifest> {
+		let file = fs::File::open(source).wrap_err("Failed to open archive")?;
+		let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+		let mut manifest: Option<BackupManifest> = None;
+		let mut contents = BTreeMap::new();
+		for entry in archive.entries().wrap_err("Failed to read archive")? {
+			let mut entry = entry.wrap_err("Failed to read archive entry")?;
+			let name = entry
+				.path()
+				.wrap_err("Archive entry has an invalid path")?
+				.to_string_lossy()
+				.to_string();
+			let mut data = Vec::new();
+			entry
+				.read_to_end(&mut data)
+				.wrap_err_with(|| format!("Failed to read {name}"))?;
+
+			if name == MANIFEST_PATH {
+				manifest = Some(serde_json::from_slice(&data).wrap_err("Failed to deserialize manifest")?);
+			} else {
+				contents.insert(name, data);
+			}
+		}
+
+		let manifest = manifest.wrap_err("Archive does not contain a manifest")?;
+		manifest.validate().wrap_err("Invalid backup manifest")?;
+
+		if manifest.files.len() != contents.len() {
+			return Err(Audit::new("Archive files do not match the manifest"));
+		}
+		for (name, expected) in &manifest.files {
+			let data = contents
+				.get(name)
+				.wrap_err_with(|| format!("Archive is missing {name}"))?;
+			if &hash(data) != expected {
+				return Err(Audit::new(format!("Checksum mismatch for {name}")));
+			}
+		}
+
+		// Stage everything next to the live directories, then swap them in.
+		let config = self.get_dir(Location::Config).to_path_buf();
+		let data = self.get_dir(Location::Data).to_path_buf();
+		let staged_config = sibling(&config, "import");
+		let staged_data = sibling(&data, "import");
+		for dir in [&staged_config, &staged_data] {
+			if dir.exists() {
+				fs::remove_dir_all(dir).wrap_err("Failed to clear old import")?;
+			}
+			fs::create_dir_all(dir).wrap_err("Failed to create import directory")?;
+		}
+
+		for (name, content) in &contents {
+			let (prefix, path) = name.split_once('/').expect("Validated by manifest");
+			let target = match prefix {
+				CONFIG_PREFIX => staged_config.join(path),
+				_ => staged_data.join(path),
+			};
+			if let Some(parent) = target.parent() {
+				fs::create_dir_all(parent).wrap_err("Failed to create import directory")?;
+			}
+			fs::write(&target, content).wrap_err_with(|| format!("Failed to stage {name}"))?;
+		}
+
+		// Keep the data of apps that are not part of this backup.
+		let apps_dir = data.join(APP_DATA_DIR);
+		if apps_dir.is_dir() {
+			for entry in fs::read_dir(&apps_dir).wrap_err("Failed to read app data")? {
+				let entry = entry.wrap_err("Failed to read app data")?;
+				let app = entry.file_name().to_string_lossy().to_string();
+				if !manifest.apps.contains(&app) {
+					copy_dir(&entry.path(), &staged_data.join(APP_DATA_DIR).join(&app))
+						.wrap_err_with(|| format!("Failed to keep data of app {app}"))?;
+				}
+			}
+		}
+
+		swap_dirs(&[(&config, &staged_config), (&data, &staged_data)])
+			.wrap_err("Failed to restore profile")?;
+
+		info!("Imported profile with {} files from {source:?}", manifest.files.len());
+		Ok(manifest)
+	}
+}
+
+/// Replaces every live directory with its staged counterpart.
+/// If any rename fails, the directories already swapped are rolled back.
+fn swap_dirs(dirs: &[(&PathBuf, &PathBuf)]) -> std::io::Result<()> {
+	let mut swapped: Vec<(&PathBuf, PathBuf)> = Vec::new();
+	for (live, staged) in dirs {
+		let old = sibling(live, "old");
+		if old.exists() {
+			fs::remove_dir_all(&old)?;
+		}
+
+		let result = fs::rename(live, &old).and_then(|_| {
+			fs::rename(staged, live).inspect_err(|_| {
+				// Put the live directory back before reporting.
+				let _ = fs::rename(&old, live);
+			})
+		});
+
+		if let Err(err) = result {
+			for (live, old) in swapped.into_iter().rev() {
+				let _ = fs::remove_dir_all(live);
+				let _ = fs::rename(&old, live);
+			}
+			return Err(err);
+		}
+		swapped.push((live, old));
+	}
+
+	for (_, old) in swapped {
+		if let Err(err) = fs::remove_dir_all(&old) {
+			warn!("Failed to remove old profile directory {old:?}: {err}");
+		}
+	}
+	Ok(())
+}
+
+fn collect_files(
+	dir: &Path,
+	prefix: &str,
+	out: &mut Vec<(String, PathBuf)>,
+	filter: &dyn Fn(&str) -> bool,
+) -> std::io::Result<()> {
+	for entry in fs::read_dir(dir)? {
+		let entry = entry?;
+		let name = entry.file_name().to_string_lossy().to_string();
+		if !filter(&name) {
+			continue;
+		}
+
+		let path = entry.path();
+		let archive_name = format!("{prefix}/{name}");
+		if entry.file_type()?.is_dir() {
+			collect_files(&path, &archive_name, out, &|_| true)?;
+		} else {
+			out.push((archive_name, path));
+		}
+	}
+	Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+	fs::create_dir_all(to)?;
+	for entry in fs::read_dir(from)? {
+		let entry = entry?;
+		let target = to.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &target)?;
+		} else {
+			fs::copy(entry.path(), target)?;
+		}
+	}
+	Ok(())
+}
+
+fn append<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+	let mut header = tar::Header::new_gnu();
+	header.set_size(data.len() as u64);
+	header.set_mode(0o644);
+	header.set_cksum();
+	builder
+		.append_data(&mut header, name, data)
+		.wrap_err_with(|| format!("Failed to write {name} to archive"))
+}
+
+fn hash(data: &[u8]) -> String {
+	format!("{:x}", Sha256::digest(data))
+}
+
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+	let mut name = dir.file_name().unwrap_or_default().to_os_string();
+	name.push(".");
+	name.push(suffix);
+	dir.with_file_name(name)
+}
+
+/// Only allow relative paths inside the config or data location.
+fn is_safe_entry(path: &str) -> bool {
+	let mut components = Path::new(path).components();
+	let valid_root = matches!(
+		components.next(),
+		Some(Component::Normal(root)) if root == CONFIG_PREFIX || root == DATA_PREFIX
+	);
+	valid_root
+		&& components.clone().next().is_some()
+		&& components.all(|component| matches!(component, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn manifest(files: &[(&str, &str)]) -> BackupManifest {
+		let files: BTreeMap<String, String> = files
+			.iter()
+			.map(|(path, hash)| (path.to_string(), hash.to_string()))
+			.collect();
+		BackupManifest {
+			version: BACKUP_VERSION,
+			created: 0,
+			apps: Vec::new(),
+			checksum: BackupManifest::compute_checksum(&files),
+			files,
+		}
+	}
+
+	#[test]
+	fn entries_stay_inside_the_profile() {
+		assert!(is_safe_entry("config/config.json"));
+		assert!(is_safe_entry("data/apps/map/routes.json"));
+		assert!(!is_safe_entry("config"));
+		assert!(!is_safe_entry("cache/tiles"));
+		assert!(!is_safe_entry("config/../../.bashrc"));
+		assert!(!is_safe_entry("data/apps/../../../etc/passwd"));
+		assert!(!is_safe_entry("/etc/passwd"));
+		assert!(!is_safe_entry("/config/config.json"));
+		assert!(!is_safe_entry("./config/config.json"));
+	}
+
+	#[test]
+	fn manifests_are_validated() {
+		assert!(manifest(&[("config/config.json", "abc")]).validate().is_ok());
+
+		let mut tampered = manifest(&[("config/config.json", "abc")]);
+		tampered.files.insert("data/token".to_string(), "def".to_string());
+		assert!(tampered.validate().is_err());
+
+		assert!(manifest(&[("data/../../.bashrc", "abc")]).validate().is_err());
+
+		let mut newer = manifest(&[]);
+		newer.version = BACKUP_VERSION + 1;
+		assert!(newer.validate().is_err());
+	}
+
+	#[test]
+	fn export_and_import_restore_the_profile() {
+		let asset = AssetManager::in_dir("backup-round-trip");
+		let config = asset.get_dir(Location::Config).to_path_buf();
+		let apps = asset.get_dir(Location::Data).join(APP_DATA_DIR);
+		fs::write(config.join("config.json"), "before").unwrap();
+		fs::create_dir_all(apps.join("map")).unwrap();
+		fs::write(apps.join("map").join("routes.json"), "home").unwrap();
+		fs::create_dir_all(apps.join("music")).unwrap();
+		fs::write(apps.join("music").join("token"), "secret").unwrap();
+
+		let archive = config.with_file_name("backup.tar.gz");
+		let manifest = asset
+			.export_profile_blocking(&archive, vec!["map".to_string()])
+			.unwrap();
+		assert_eq!(manifest.files.len(), 2);
+
+		fs::write(config.join("config.json"), "after").unwrap();
+		fs::remove_dir_all(apps.join("map")).unwrap();
+		asset.import_profile_blocking(&archive).unwrap();
+
+		assert_eq!(fs::read_to_string(config.join("config.json")).unwrap(), "before");
+		assert_eq!(fs::read_to_string(apps.join("map").join("routes.json")).unwrap(), "home");
+		// Apps that are not in the backup keep their data.
+		assert_eq!(fs::read_to_string(apps.join("music").join("token")).unwrap(), "secret");
+	}
+
+	#[test]
+	fn tampered_archives_are_not_imported() {
+		let asset = AssetManager::in_dir("backup-tampered");
+		let config = asset.get_dir(Location::Config).to_path_buf();
+		fs::write(config.join("config.json"), "original").unwrap();
+
+		let archive = config.with_file_name("tampered.tar.gz");
+		let manifest = manifest(&[("config/config.json", &hash(b"expected"))]);
+		let mut builder = tar::Builder::new(GzEncoder::new(
+			fs::File::create(&archive).unwrap(),
+			Compression::default(),
+		));
+		append(&mut builder, MANIFEST_PATH, &serde_json::to_vec(&manifest).unwrap()).unwrap();
+		append(&mut builder, "config/config.json", b"tampered").unwrap();
+		builder.into_inner().unwrap().finish().unwrap();
+
+		assert!(asset.import_profile_blocking(&archive).is_err());
+		assert_eq!(fs::read_to_string(config.join("config.json")).unwrap(), "original");
+	}
+
+	#[test]
+	fn failed_swaps_are_rolled_back() {
+		let asset = AssetManager::in_dir("backup-rollback");
+		let config = asset.get_dir(Location::Config).to_path_buf();
+		let data = asset.get_dir(Location::Data).to_path_buf();
+		fs::write(config.join("config.json"), "live").unwrap();
+		fs::write(data.join("data.json"), "live").unwrap();
+		let staged_config = sibling(&config, "import");
+		fs::create_dir_all(&staged_config).unwrap();
+		fs::write(staged_config.join("config.json"), "staged").unwrap();
+		// There is no staged data, so the second swap fails after the first one went through.
+		let staged_data = sibling(&data, "import");
+
+		assert!(swap_dirs(&[(&config, &staged_config), (&data, &staged_data)]).is_err());
+		assert_eq!(fs::read_to_string(config.join("config.json")).unwrap(), "live");
+		assert_eq!(fs::read_to_string(data.join("data.json")).unwrap(), "live");
+		assert!(!sibling(&config, "old").exists());
+		assert!(!sibling(&data, "old").exists());
+	}
+}
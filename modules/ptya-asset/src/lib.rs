@@ -7,12 +7,22 @@ use tokio::fs::{create_dir_all, read_dir, OpenOptions};
 use tokio::io;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+mod backup;
+mod profile;
+
+pub use crate::backup::{BackupManifest, APP_DATA_DIR, BACKUP_VERSION};
+pub use crate::profile::{Profile, Profiles, DEFAULT_PROFILE};
+
 #[derive(Clone)]
 pub struct AssetManager {
 	assets: PathBuf,
 	data: PathBuf,
 	config: PathBuf,
 	cache: PathBuf,
+	// The directories shared by every profile.
+	data_root: PathBuf,
+	config_root: PathBuf,
+	profile: String,
 }
 
 impl AssetManager {
@@ -23,6 +33,9 @@ impl AssetManager {
 			data: PathBuf::from("./home/data"),
 			config: PathBuf::from("./home/config"),
 			cache: PathBuf::from("./home/cache"),
+			data_root: PathBuf::from("./home/data"),
+			config_root: PathBuf::from("./home/config"),
+			profile: String::new(),
 		};
 		#[cfg(not(debug_assertions))]
 		let comp = AssetManager {
@@ -36,6 +49,13 @@ impl AssetManager {
 			cache: dirs::cache_dir()
 				.expect("Could not find the cache directory")
 				.join("pitaya"),
+			data_root: dirs::data_dir()
+				.expect("Could not find the data directory")
+				.join("pitaya"),
+			config_root: dirs::config_dir()
+				.expect("Could not find the config directory")
+				.join("pitaya"),
+			profile: String::new(),
 		};
 
 		create_dir_all(&comp.assets).await?;
@@ -43,11 +63,18 @@ impl AssetManager {
 		create_dir_all(&comp.config).await?;
 		create_dir_all(&comp.cache).await?;
 
+		let profiles = comp.profiles().await.wrap_err("Failed to read profiles")?;
+		let comp = comp
+			.select_profile(&profiles.active)
+			.await
+			.wrap_err("Failed to select active profile")?;
+
 		info!("Created asset manager");
 
 		Ok(comp)
 	}
 
+	/// Gets the directory of a location. [Location::Data] and [Location::Config] point to the selected profile.
 	pub fn get_dir(&self, loc: Location) -> &Path {
 		match loc {
 			Location::Assets => &self.assets,
@@ -136,7 +163,7 @@ impl AssetManager {
 		let mut file = OpenOptions::new()
 			.create(true)
 			.write(true)
-			.truncate(false)
+			.truncate(true)
 			.open(path)
 			.await?;
 		file.write_all(data).await?;
@@ -144,6 +171,28 @@ impl AssetManager {
 	}
 }
 
+#[cfg(test)]
+impl AssetManager {
+	/// Creates an asset manager with every location in a new temporary directory.
+	pub(crate) fn in_dir(name: &str) -> AssetManager {
+		let root = std::env::temp_dir().join(format!("ptya-asset-{name}-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&root);
+		let comp = AssetManager {
+			assets: root.join("assets"),
+			data: root.join("data"),
+			config: root.join("config"),
+			cache: root.join("cache"),
+			data_root: root.join("data"),
+			config_root: root.join("config"),
+			profile: String::new(),
+		};
+		for dir in [&comp.assets, &comp.data, &comp.config, &comp.cache] {
+			std::fs::create_dir_all(dir).expect("Failed to create test directory");
+		}
+		comp
+	}
+}
+
 #[derive(Copy, Clone)]
 pub enum Location {
 	/// The Asset location which contains this installations assets.
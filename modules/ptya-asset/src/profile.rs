@@ -0,0 +1,234 @@
+use crate::AssetManager;
+use anyways::audit::Audit;
+use anyways::ext::AuditExt;
+use anyways::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs::{create_dir_all, read_dir, rename};
+
+/// The id of the profile every installation starts with.
+pub const DEFAULT_PROFILE: &str = "default";
+
+const PROFILES_PATH: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+
+/// The driver profiles of this installation.
+/// This lives in the shared config root, every profile gets its own [Location::Config](crate::Location::Config)
+/// and [Location::Data](crate::Location::Data) directory.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profiles {
+	pub profiles: Vec<Profile>,
+	/// The profile that gets selected on boot.
+	pub active: String,
+	/// Asks which profile to use on every boot instead of using the active one.
+	#[serde(default)]
+	pub pick_on_boot: bool,
+}
+
+impl Profiles {
+	pub fn get(&self, id: &str) -> Option<&Profile> {
+		self.profiles.iter().find(|profile| profile.id == id)
+	}
+}
+
+impl Default for Profiles {
+	fn default() -> Self {
+		Profiles {
+			profiles: vec![Profile {
+				id: DEFAULT_PROFILE.to_string(),
+				name: "Default".to_string(),
+			}],
+			active: DEFAULT_PROFILE.to_string(),
+			pick_on_boot: false,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Profile {
+	pub id: String,
+	pub name: String,
+}
+
+impl AssetManager {
+	/// The id of the currently selected profile.
+	pub fn profile(&self) -> &str {
+		&self.profile
+	}
+
+	/// Reads the profile list, creating the default profile if there is none yet.
+	pub async fn profiles(&self) -> Result<Profiles> {
+		let path = self.config_root.join(PROFILES_PATH);
+		if tokio::fs::metadata(&path).await.is_ok() {
+			let data = tokio::fs::read(&path)
+				.await
+				.wrap_err("Failed to read profiles")?;
+			serde_json::from_slice(&data).wrap_err("Failed to deserialize profiles")
+		} else {
+			self.migrate_to_profiles()
+				.await
+				.wrap_err("Failed to migrate to profiles")?;
+			let profiles = Profiles::default();
+			self.save_profiles(&profiles).await?;
+			Ok(profiles)
+		}
+	}
+
+	pub async fn save_profiles(&self, profiles: &Profiles) -> Result<()> {
+		let data = serde_json::to_vec_pretty(profiles).wrap_err("Failed to serialize profiles")?;
+		tokio::fs::write(self.config_root.join(PROFILES_PATH), data)
+			.await
+			.wrap_err("Failed to write profiles")?;
+		Ok(())
+	}
+
+	/// Creates a new profile with an id derived from its name.
+	pub async fn create_profile(&self, name: impl Into<String>) -> Result<Profile> {
+		let name = name.into();
+		let mut profiles = self.profiles().await?;
+
+		let base: String = name
+			.chars()
+			.map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+			.collect();
+		let base = if base.is_empty() { "profile".to_string() } else { base };
+		let mut id = base.clone();
+		let mut i = 1;
+		while profiles.get(&id).is_some() {
+			i += 1;
+			id = format!("{base}_{i}");
+		}
+
+		let profile = Profile { id, name };
+		profiles.profiles.push(profile.clone());
+		self.save_profiles(&profiles).await?;
+		info!("Created profile {:?}", profile.id);
+		Ok(profile)
+	}
+
+	/// Sets if the profile picker is shown on every boot.
+	pub async fn set_pick_on_boot(&self, pick_on_boot: bool) -> Result<()> {
+		let mut profiles = self.profiles().await?;
+		profiles.pick_on_boot = pick_on_boot;
+		self.save_profiles(&profiles).await
+	}
+
+	/// Returns an [AssetManager] that points to the given profile and remembers it for the next boot.
+	pub async fn select_profile(&self, id: &str) -> Result<AssetManager> {
+		// The id becomes a directory name, a hand edited profile list could point it outside of the profiles.
+		if !is_valid_id(id) {
+			return Err(Audit::new(format!("Profile id {id:?} may only contain a-z, 0-9 and _")));
+		}
+		let mut profiles = self.profiles().await?;
+		if profiles.get(id).is_none() {
+			return Err(Audit::new(format!("Profile {id:?} does not exist")));
+		}
+
+		let mut asset = self.clone();
+		asset.profile = id.to_string();
+		asset.data = self.data_root.join(PROFILES_DIR).join(id);
+		asset.config = self.config_root.join(PROFILES_DIR).join(id);
+		create_dir_all(&asset.data).await?;
+		create_dir_all(&asset.config).await?;
+
+		if profiles.active != id {
+			profiles.active = id.to_string();
+			self.save_profiles(&profiles).await?;
+		}
+
+		info!("Selected profile {id:?}");
+		Ok(asset)
+	}
+
+	/// Installations from before profiles keep their files directly in the roots, these become the default profile.
+	async fn migrate_to_profiles(&self) -> std::io::Result<()> {
+		for root in [&self.config_root, &self.data_root] {
+			let target = root.join(PROFILES_DIR).join(DEFAULT_PROFILE);
+			create_dir_all(&target).await?;
+			move_entries(root, &target).await?;
+		}
+		Ok(())
+	}
+}
+
+/// Checks if an id only uses the characters [AssetManager::create_profile] makes ids from.
+fn is_valid_id(id: &str) -> bool {
+	!id.is_empty() && id.chars().all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+}
+
+async fn move_entries(from: &Path, to: &Path) -> std::io::Result<()> {
+	let mut entries = read_dir(from).await?;
+	while let Some(entry) = entries.next_entry().await? {
+		let name = entry.file_name();
+		if name != PROFILES_DIR && name != PROFILES_PATH {
+			info!("Moving {name:?} into the default profile");
+			rename(entry.path(), to.join(name)).await?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Location;
+
+	#[tokio::test]
+	async fn old_files_move_into_the_default_profile() {
+		let asset = AssetManager::in_dir("profile-migrate");
+		tokio::fs::write(asset.config_root.join("config.json"), "config").await.unwrap();
+		tokio::fs::write(asset.data_root.join("token"), "data").await.unwrap();
+
+		let profiles = asset.profiles().await.unwrap();
+		assert_eq!(profiles.active, DEFAULT_PROFILE);
+		let asset = asset.select_profile(DEFAULT_PROFILE).await.unwrap();
+		assert_eq!(asset.read_file(Location::Config, "config.json").await.unwrap(), b"config");
+		assert_eq!(asset.read_file(Location::Data, "token").await.unwrap(), b"data");
+		// The profile list stays in the shared root.
+		assert!(!asset.contains_file(Location::Config, PROFILES_PATH).await);
+		assert!(asset.config_root.join(PROFILES_PATH).exists());
+	}
+
+	#[tokio::test]
+	async fn profiles_keep_their_own_files() {
+		let asset = AssetManager::in_dir("profile-select");
+		let first = asset.create_profile("Alex").await.unwrap();
+		let second = asset.create_profile("Alex").await.unwrap();
+		assert_eq!(first.id, "alex");
+		assert_eq!(second.id, "alex_2");
+		assert_eq!(asset.create_profile("").await.unwrap().id, "profile");
+
+		let alex = asset.select_profile(&first.id).await.unwrap();
+		alex.save_data(Location::Config, "config.json", &1u32).await.unwrap();
+		let other = asset.select_profile(&second.id).await.unwrap();
+		assert_eq!(other.profile(), "alex_2");
+		assert!(!other.contains_file(Location::Config, "config.json").await);
+		assert!(alex.contains_file(Location::Config, "config.json").await);
+
+		// The last selected profile is used on the next boot.
+		assert_eq!(asset.profiles().await.unwrap().active, "alex_2");
+		assert!(asset.select_profile("missing").await.is_err());
+	}
+
+	#[tokio::test]
+	async fn profile_ids_stay_in_the_profiles_dir() {
+		let asset = AssetManager::in_dir("profile-escape");
+		let mut profiles = asset.profiles().await.unwrap();
+		for id in ["../x", "a/b", "", "Alex"] {
+			profiles.profiles.push(Profile {
+				id: id.to_string(),
+				name: id.to_string(),
+			});
+		}
+		asset.save_profiles(&profiles).await.unwrap();
+
+		for id in ["../x", "a/b", "", "Alex"] {
+			assert!(asset.select_profile(id).await.is_err(), "{id:?}");
+		}
+		assert!(!asset.config_root.join("x").exists());
+
+		asset.set_pick_on_boot(true).await.unwrap();
+		assert!(asset.profiles().await.unwrap().pick_on_boot);
+	}
+}
@@ -2,10 +2,13 @@ use material_color_utilities_rs::blend::harmonize;
 use material_color_utilities_rs::palettes::core::CorePalette;
 use material_color_utilities_rs::palettes::tonal::TonalPalette;
 use egui::Color32;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ptya_animation::Lerp;
+use crate::config::Contrast;
 use crate::theme::color32_from_argb;
 
-#[derive(Clone, PartialEq, Eq, Default)]
+#[derive(Clone, PartialEq, Eq, Default, Lerp)]
 pub struct ColorGroup {
 	pub color: Color32,
 	pub on_color: Color32,
@@ -15,48 +18,118 @@ pub struct ColorGroup {
 
 impl ColorGroup {
 	pub async fn new_custom(source: [u8; 4], color: [u8; 4], dark_mode: bool) -> ColorGroup {
-		let value = harmonize(color, source);
-		let mut palette = CorePalette::new(value, false);
-		Self::new_tonal(&mut palette.a1, dark_mode)
+		Self::new_harmonized(source, color, GroupTones::new(dark_mode, Contrast::Standard))
+	}
+
+	/// Creates a color group for a color that is shifted towards the source color so it fits in.
+	pub fn new_harmonized(source: [u8; 4], color: [u8; 4], tones: GroupTones) -> ColorGroup {
+		Self::new_source(harmonize(color, source), tones)
 	}
 	
+	/// Creates a color group that keeps the hue of the color.
+	pub fn new_source(color: [u8; 4], tones: GroupTones) -> ColorGroup {
+		let mut palette = CorePalette::new(color, false);
+		Self::from_tones(&mut palette.a1, tones)
+	}
+
 	pub fn new_tonal(tones: &mut TonalPalette, dark_mode: bool) -> ColorGroup {
-		if dark_mode {
-			ColorGroup {
-				color: color32_from_argb(tones.tone(80)),
-				on_color: color32_from_argb(tones.tone(20)),
-				color_container: color32_from_argb(tones.tone(30)),
-				on_color_container: color32_from_argb(tones.tone(90)),
-			}
-		} else {
-			ColorGroup {
-				color: color32_from_argb(tones.tone(40)),
-				on_color: color32_from_argb(tones.tone(100)),
-				color_container: color32_from_argb(tones.tone(90)),
-				on_color_container: color32_from_argb(tones.tone(10)),
-			}
+		Self::from_tones(tones, GroupTones::new(dark_mode, Contrast::Standard))
+	}
+
+	pub fn from_tones(palette: &mut TonalPalette, tones: GroupTones) -> ColorGroup {
+		ColorGroup {
+			color: color32_from_argb(palette.tone(tones.color)),
+			on_color: color32_from_argb(palette.tone(tones.on_color)),
+			color_container: color32_from_argb(palette.tone(tones.color_container)),
+			on_color_container: color32_from_argb(palette.tone(tones.on_color_container)),
 		}
 	}
 }
 
-impl Lerp for ColorGroup {
-	fn lerp_static(v0: &Self, v1: &Self, t: f32) -> Self {
-		ColorGroup {
-			color: v0.color.lerp(&v1.color, t),
-			on_color: v0.on_color.lerp(&v1.on_color, t),
-			color_container: v0.color_container.lerp(&v1.color_container, t),
-			on_color_container: v0.on_color_container.lerp(&v1.on_color_container, t)
+/// The tones that the colors of a [ColorGroup] are taken from.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GroupTones {
+	pub color: u8,
+	pub on_color: u8,
+	pub color_container: u8,
+	pub on_color_container: u8,
+}
+
+impl GroupTones {
+	pub fn new(dark_mode: bool, contrast: Contrast) -> GroupTones {
+		let [color, on_color, color_container, on_color_container] = match (dark_mode, contrast) {
+			(true, Contrast::Standard) => [80, 20, 30, 90],
+			(true, Contrast::High) => [90, 10, 30, 100],
+			(false, Contrast::Standard) => [40, 100, 90, 10],
+			(false, Contrast::High) => [30, 100, 90, 0],
+		};
+		GroupTones {
+			color,
+			on_color,
+			color_container,
+			on_color_container,
 		}
 	}
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum ColorTag {
 	Primary,
 	Secondary,
 	Tertiary,
+	Error,
 	Red,
 	Orange,
 	Yellow,
 	Green,
-	Blue
-}
\ No newline at end of file
+	Blue,
+	/// A color that an app registered, see [ColorManager::register_color](crate::ColorManager::register_color).
+	/// Falls back to the primary color when nothing is registered under the name.
+	Custom(&'static str),
+}
+
+impl ColorTag {
+	pub const BUILT_IN: [ColorTag; 9] = [
+		ColorTag::Primary,
+		ColorTag::Secondary,
+		ColorTag::Tertiary,
+		ColorTag::Error,
+		ColorTag::Red,
+		ColorTag::Orange,
+		ColorTag::Yellow,
+		ColorTag::Green,
+		ColorTag::Blue,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			ColorTag::Primary => "primary",
+			ColorTag::Secondary => "secondary",
+			ColorTag::Tertiary => "tertiary",
+			ColorTag::Error => "error",
+			ColorTag::Red => "red",
+			ColorTag::Orange => "orange",
+			ColorTag::Yellow => "yellow",
+			ColorTag::Green => "green",
+			ColorTag::Blue => "blue",
+			ColorTag::Custom(name) => name,
+		}
+	}
+}
+
+impl Serialize for ColorTag {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.name())
+	}
+}
+
+/// Only the built in tags can be read, custom tags only exist while their app is loaded.
+impl<'de> Deserialize<'de> for ColorTag {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		Self::BUILT_IN
+			.into_iter()
+			.find(|tag| tag.name() == name)
+			.ok_or_else(|| D::Error::custom(format!("Unknown color {name:?}")))
+	}
+}
@@ -1,23 +1,112 @@
+use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct ColorConfig {
+    /// Used when there is no schedule, or the schedule can not decide.
     pub dark_mode: bool,
     pub theme: ThemeTag,
+    #[serde(default)]
+    pub schedule: ThemeSchedule,
+    /// The last known latitude and longitude of the vehicle, used for [ThemeSchedule::Sun].
+    #[serde(default)]
+    pub location: Option<[f64; 2]>,
+    #[serde(default)]
+    pub light_sensor: LightSensorConfig,
+    #[serde(default)]
+    pub contrast: Contrast,
+    #[serde(default)]
+    pub color_vision: ColorVision,
+    /// Adds icons to components that would otherwise only use red and green to tell things apart.
+    /// These are always shown when `color_vision` is not [ColorVision::Normal].
+    #[serde(default)]
+    pub icon_cues: bool,
 }
 
 impl Default for ColorConfig {
     fn default() -> Self {
         ColorConfig {
             dark_mode: true,
-            theme: Default::default()
+            theme: Default::default(),
+            schedule: Default::default(),
+            location: None,
+            light_sensor: Default::default(),
+            contrast: Default::default(),
+            color_vision: Default::default(),
+            icon_cues: false,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+/// When to switch between the dark and light theme.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Debug)]
+pub enum ThemeSchedule {
+    /// Always follows `dark_mode`.
+    #[default]
+    Off,
+    /// Switches at fixed local times.
+    Times { light: NaiveTime, dark: NaiveTime },
+    /// Switches at sunrise and sunset of the last known location.
+    Sun,
+}
+
+/// Ambient light thresholds in lux, the gap between them stops the theme from flickering at dusk.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct LightSensorConfig {
+    pub dark_below: f32,
+    pub light_above: f32,
+    /// Switches to high contrast in direct sunlight, where glare makes the screen hard to read.
+    pub glare_above: f32,
+}
+
+impl Default for LightSensorConfig {
+    fn default() -> Self {
+        LightSensorConfig {
+            dark_below: 50.0,
+            light_above: 400.0,
+            glare_above: 30000.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
 pub enum ThemeTag {
     #[default]
     Pitaya,
     Custom([u8; 3]),
-}
\ No newline at end of file
+    /// A [ThemeFile](crate::ThemeFile) by its name, falls back to [ThemeTag::Pitaya] when it is missing.
+    File(String),
+}
+
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Contrast {
+    /// Text meets WCAG AA.
+    #[default]
+    Standard,
+    /// Text meets WCAG AAA, with tones that are further apart.
+    High,
+}
+
+impl Contrast {
+    /// The minimum contrast ratio between text and its background.
+    pub fn min_ratio(self) -> f64 {
+        match self {
+            Contrast::Standard => 4.5,
+            Contrast::High => 7.0,
+        }
+    }
+}
+
+/// The color vision of the driver, anything other than normal moves the semantic colors apart.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorVision {
+    #[default]
+    Normal,
+    /// Red blindness, red and green look alike and red looks dark.
+    Protan,
+    /// Green blindness, the most common one, red and green look alike.
+    Deutan,
+    /// Blue blindness, blue and green and yellow and pink look alike.
+    Tritan,
+}
@@ -0,0 +1,217 @@
+use crate::config::Contrast;
+use crate::{ColorGroup, Theme};
+use egui::Color32;
+use ptya_animation::Lerp;
+
+/// The minimum contrast for outlines and other graphics that are not text.
+const GRAPHIC_RATIO: f64 = 3.0;
+
+/// A foreground and background color from a theme that get drawn on top of each other.
+struct ContrastPair {
+	name: &'static str,
+	fg: fn(&Theme) -> Color32,
+	fg_mut: fn(&mut Theme) -> &mut Color32,
+	bg: fn(&Theme) -> Color32,
+	// Graphics only need to be visible, text needs to be readable.
+	graphic: bool,
+}
+
+macro_rules! pair {
+	($($fg:ident).+ on $($bg:ident).+) => {
+		pair!($($fg).+ on $($bg).+, false)
+	};
+	($($fg:ident).+ on $($bg:ident).+, $graphic:expr) => {
+		ContrastPair {
+			name: concat!(stringify!($($fg).+), " on ", stringify!($($bg).+)),
+			fg: |theme| theme.$($fg).+,
+			fg_mut: |theme| &mut theme.$($fg).+,
+			bg: |theme| theme.$($bg).+,
+			graphic: $graphic,
+		}
+	};
+}
+
+macro_rules! group_pairs {
+	($($group:ident),+) => {
+		[$(
+			pair!($group.on_color on $group.color),
+			pair!($group.on_color_container on $group.color_container),
+		)+]
+	};
+}
+
+fn pairs() -> Vec<ContrastPair> {
+	let mut pairs = vec![
+		pair!(fg on surface),
+		pair!(fg on surface_dim),
+		pair!(fg on surface_bright),
+		pair!(fg on surface_container_lowest),
+		pair!(fg on surface_container_low),
+		pair!(fg on surface_container),
+		pair!(fg on surface_container_high),
+		pair!(fg on surface_container_highest),
+		pair!(on_surface_variant on surface),
+		pair!(on_surface_variant on surface_variant),
+		pair!(inverse_on_surface on inverse_surface),
+		pair!(outline on surface, true),
+	];
+	pairs.extend(group_pairs!(
+		primary, secondary, tertiary, error, red, orange, yellow, green, blue
+	));
+	pairs
+}
+
+/// A color pair that does not have enough contrast.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContrastIssue {
+	pub pair: String,
+	pub ratio: f64,
+	pub required: f64,
+}
+
+impl Theme {
+	/// Checks the contrast of every foreground and background role pair of the theme, custom colors included.
+	pub fn check_contrast(&self, contrast: Contrast) -> Vec<ContrastIssue> {
+		let custom = self
+			.custom
+			.iter()
+			.flat_map(|(name, group)| group.check_contrast(name, contrast));
+		pairs()
+			.into_iter()
+			.filter_map(|pair| {
+				let required = pair.required(contrast);
+				let ratio = contrast_ratio((pair.fg)(self), (pair.bg)(self));
+				(ratio < required).then(|| ContrastIssue {
+					pair: pair.name.to_string(),
+					ratio,
+					required,
+				})
+			})
+			.chain(custom)
+			.collect()
+	}
+
+	/// Moves foreground colors towards black or white until every pair has enough contrast.
+	pub fn enforce_contrast(&mut self, contrast: Contrast) {
+		for pair in pairs() {
+			let required = pair.required(contrast);
+			let bg = (pair.bg)(self);
+			let fg = (pair.fg_mut)(self);
+			*fg = ensure_contrast(*fg, bg, required);
+		}
+		for group in self.custom.values_mut() {
+			group.enforce_contrast(contrast);
+		}
+	}
+}
+
+impl ColorGroup {
+	fn check_contrast(&self, name: &str, contrast: Contrast) -> Vec<ContrastIssue> {
+		let required = contrast.min_ratio();
+		[
+			("on_color", self.on_color, "color", self.color),
+			("on_color_container", self.on_color_container, "color_container", self.color_container),
+		]
+		.into_iter()
+		.filter_map(|(fg_name, fg, bg_name, bg)| {
+			let ratio = contrast_ratio(fg, bg);
+			(ratio < required).then(|| ContrastIssue {
+				pair: format!("{name}.{fg_name} on {name}.{bg_name}"),
+				ratio,
+				required,
+			})
+		})
+		.collect()
+	}
+
+	/// Moves the text colors of the group towards black or white until they are readable.
+	pub fn enforce_contrast(&mut self, contrast: Contrast) {
+		let required = contrast.min_ratio();
+		self.on_color = ensure_contrast(self.on_color, self.color, required);
+		self.on_color_container = ensure_contrast(self.on_color_container, self.color_container, required);
+	}
+}
+
+impl ContrastPair {
+	fn required(&self, contrast: Contrast) -> f64 {
+		if self.graphic {
+			GRAPHIC_RATIO
+		} else {
+			contrast.min_ratio()
+		}
+	}
+}
+
+/// The relative luminance as defined by WCAG.
+pub fn relative_luminance(color: Color32) -> f64 {
+	let channel = |value: u8| {
+		let value = value as f64 / 255.0;
+		if value <= 0.04045 {
+			value / 12.92
+		} else {
+			((value + 0.055) / 1.055).powf(2.4)
+		}
+	};
+	0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+}
+
+/// The WCAG contrast ratio between two colors, from 1 for the same colors up to 21 for black on white.
+pub fn contrast_ratio(a: Color32, b: Color32) -> f64 {
+	let (a, b) = (relative_luminance(a), relative_luminance(b));
+	(a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Moves `fg` towards black or white until it has at least `ratio` contrast against `bg`.
+pub fn ensure_contrast(fg: Color32, bg: Color32, ratio: f64) -> Color32 {
+	if contrast_ratio(fg, bg) >= ratio {
+		return fg;
+	}
+
+	let target = if contrast_ratio(Color32::WHITE, bg) > contrast_ratio(Color32::BLACK, bg) {
+		Color32::WHITE
+	} else {
+		Color32::BLACK
+	};
+	(1..=20)
+		.map(|step| fg.lerp(&target, step as f32 / 20.0))
+		.find(|color| contrast_ratio(*color, bg) >= ratio)
+		.unwrap_or(target)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wcag_ratios() {
+		assert!((contrast_ratio(Color32::BLACK, Color32::WHITE) - 21.0).abs() < 1e-9);
+		assert_eq!(contrast_ratio(Color32::RED, Color32::RED), 1.0);
+	}
+
+	#[test]
+	fn themes_are_readable() {
+		// The tones are picked to be readable, enforcing contrast is only a safety net for them.
+		for seed in [[0xe5, 0x4c, 0x64], [0xff, 0xff, 0x00], [0x00, 0xff, 0xff], [0x80, 0x80, 0x80]] {
+			for dark_mode in [true, false] {
+				for contrast in [Contrast::Standard, Contrast::High] {
+					let theme = Theme::with_contrast(seed, dark_mode, contrast);
+					let issues = theme.check_contrast(contrast);
+					assert!(issues.is_empty(), "{seed:?} {dark_mode} {contrast:?} {issues:?}");
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn unreadable_pairs_are_reported_and_fixed() {
+		let mut theme = Theme::from_seed([0xe5, 0x4c, 0x64], true);
+		theme.fg = theme.surface;
+		let issues = theme.check_contrast(Contrast::Standard);
+		let issue = issues.iter().find(|issue| issue.pair == "fg on surface").unwrap();
+		assert_eq!(issue.ratio, 1.0);
+		assert_eq!(issue.required, 4.5);
+
+		theme.enforce_contrast(Contrast::Standard);
+		assert!(theme.check_contrast(Contrast::Standard).is_empty());
+	}
+}
@@ -0,0 +1,112 @@
+use egui::Color32;
+use material_color_utilities_rs::score::score;
+use material_color_utilities_rs::util::color::{argb_from_lab, lab_from_argb};
+use std::collections::HashMap;
+
+/// Roughly the amount of pixels that get looked at, large images are sampled.
+const MAX_SAMPLES: usize = 128 * 128;
+/// The amount of colors the image gets reduced to before scoring.
+const MAX_COLORS: usize = 128;
+const ITERATIONS: usize = 10;
+
+/// Picks a seed color for a [Theme](crate::Theme) from an image, like album art or a wallpaper.
+/// The image is quantized to a small set of colors which are scored on how common and how colorful they are,
+/// this is the same idea as material dynamic color. Transparent pixels are ignored.
+/// This is not cheap, run it when the image changes and keep the result around.
+pub fn seed_from_image(pixels: &[Color32]) -> [u8; 3] {
+	let colors = quantize(pixels);
+	let best = score(&colors)[0];
+	[best[1], best[2], best[3]]
+}
+
+/// Reduces the image to at most [MAX_COLORS] colors with their population.
+fn quantize(pixels: &[Color32]) -> HashMap<[u8; 4], u32> {
+	// Bin the pixels first, k-means over every pixel would be far too slow.
+	let step = (pixels.len() / MAX_SAMPLES).max(1);
+	let mut bins: HashMap<u16, ([u32; 3], u32)> = HashMap::new();
+	for pixel in pixels.iter().step_by(step).filter(|pixel| pixel.a() == 255) {
+		let key = ((pixel.r() as u16 >> 3) << 10) | ((pixel.g() as u16 >> 3) << 5) | (pixel.b() as u16 >> 3);
+		let (sum, count) = bins.entry(key).or_default();
+		sum[0] += pixel.r() as u32;
+		sum[1] += pixel.g() as u32;
+		sum[2] += pixel.b() as u32;
+		*count += 1;
+	}
+
+	let mut points: Vec<([f64; 3], f64)> = bins
+		.into_values()
+		.map(|(sum, count)| {
+			let argb = [
+				0xff,
+				(sum[0] / count) as u8,
+				(sum[1] / count) as u8,
+				(sum[2] / count) as u8,
+			];
+			(lab_from_argb(argb), count as f64)
+		})
+		.collect();
+	points.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+	// Weighted k-means in Lab, starting from the most common bins.
+	let mut clusters: Vec<[f64; 3]> = points.iter().take(MAX_COLORS).map(|(lab, _)| *lab).collect();
+	let mut populations = vec![0.0; clusters.len()];
+	for _ in 0..ITERATIONS {
+		let mut sums = vec![([0.0; 3], 0.0); clusters.len()];
+		for (lab, weight) in &points {
+			let nearest = clusters
+				.iter()
+				.enumerate()
+				.map(|(i, cluster)| (i, distance(lab, cluster)))
+				.min_by(|(_, a), (_, b)| a.total_cmp(b))
+				.map(|(i, _)| i)
+				.unwrap();
+			let (sum, total) = &mut sums[nearest];
+			for channel in 0..3 {
+				sum[channel] += lab[channel] * weight;
+			}
+			*total += weight;
+		}
+
+		for (cluster, (sum, total)) in clusters.iter_mut().zip(&sums) {
+			if *total > 0.0 {
+				*cluster = sum.map(|channel| channel / total);
+			}
+		}
+		populations = sums.iter().map(|(_, total)| *total).collect();
+	}
+
+	let mut colors = HashMap::new();
+	for (lab, population) in clusters.iter().zip(populations) {
+		if population > 0.0 {
+			*colors.entry(argb_from_lab(lab[0], lab[1], lab[2])).or_default() += population as u32;
+		}
+	}
+	colors
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+	(a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn picks_the_dominant_color() {
+		// Mostly a saturated blue with some grey and a few red pixels.
+		let mut pixels = vec![Color32::from_rgb(30, 60, 200); 6000];
+		pixels.extend(vec![Color32::from_gray(128); 3000]);
+		pixels.extend(vec![Color32::from_rgb(220, 30, 30); 500]);
+		pixels.extend(vec![Color32::TRANSPARENT; 5000]);
+
+		let [r, g, b] = seed_from_image(&pixels);
+		assert!(b > 150 && r < 80 && g < 100, "{:?}", [r, g, b]);
+	}
+
+	#[test]
+	fn empty_image_has_a_seed() {
+		seed_from_image(&[]);
+		seed_from_image(&[Color32::BLACK; 100]);
+	}
+}
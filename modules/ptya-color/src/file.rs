@@ -0,0 +1,253 @@
+use crate::color::{ColorGroup, ColorTag, GroupTones};
+use crate::config::{ColorVision, Contrast};
+use crate::theme::Theme;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// A theme defined in a json file, these are loaded from `themes/` in the assets and config locations.
+/// The seed builds a full theme like [ThemeTag::Custom](crate::config::ThemeTag::Custom),
+/// after that any role can be set to an exact color, for brand colors that must not be harmonized.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeFile {
+	/// The name the theme is selected by with [ThemeTag::File](crate::config::ThemeTag::File).
+	pub name: String,
+	pub seed: HexColor,
+	#[serde(default)]
+	pub dark: ThemeOverrides,
+	#[serde(default)]
+	pub light: ThemeOverrides,
+	/// The corner radius of components.
+	#[serde(default)]
+	pub rounding: Option<f32>,
+	#[serde(default)]
+	pub typography: Typography,
+}
+
+impl ThemeFile {
+	/// Checks the parts of the file that can not be checked while parsing.
+	/// Poor contrast is not an error, brand colors win over it, see [Theme::check_contrast].
+	pub fn validate(&self) -> Result<(), ThemeFileError> {
+		if self.name.trim().is_empty() {
+			return Err(ThemeFileError::EmptyName);
+		}
+		if let Some(rounding) = self.rounding {
+			if !(0.0..=100.0).contains(&rounding) {
+				return Err(ThemeFileError::Rounding(rounding));
+			}
+		}
+		if !(0.5..=2.0).contains(&self.typography.scale) {
+			return Err(ThemeFileError::FontScale(self.typography.scale));
+		}
+		Ok(())
+	}
+
+	/// Creates the theme, overridden colors are used exactly as they are and skip [Theme::enforce_contrast].
+	pub fn build(&self, dark_mode: bool, contrast: Contrast, vision: ColorVision) -> Theme {
+		let seed = self.seed.0;
+		let mut theme = Theme::with_vision([seed.r(), seed.g(), seed.b()], dark_mode, contrast, vision);
+		theme.enforce_contrast(contrast);
+		if let Some(rounding) = self.rounding {
+			theme.rounding = rounding;
+		}
+
+		let overrides = if dark_mode { &self.dark } else { &self.light };
+		overrides.apply(&mut theme, GroupTones::new(dark_mode, contrast));
+		theme
+	}
+}
+
+/// Colors that replace the ones created from the seed, for either the dark or the light theme.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeOverrides {
+	pub groups: BTreeMap<ColorTag, GroupOverride>,
+	pub neutrals: BTreeMap<NeutralRole, HexColor>,
+}
+
+impl ThemeOverrides {
+	pub fn apply(&self, theme: &mut Theme, tones: GroupTones) {
+		for (tag, group) in &self.groups {
+			let target = theme.get_mut(*tag);
+			if let Some(source) = group.source {
+				*target = ColorGroup::new_source(source.argb(), tones);
+			}
+			for (color, value) in [
+				(&mut target.color, group.color),
+				(&mut target.on_color, group.on_color),
+				(&mut target.color_container, group.color_container),
+				(&mut target.on_color_container, group.on_color_container),
+			] {
+				if let Some(value) = value {
+					*color = value.0;
+				}
+			}
+		}
+
+		for (role, color) in &self.neutrals {
+			*theme.neutral_mut(*role) = color.0;
+		}
+		theme.bg = theme.surface;
+	}
+}
+
+/// Overrides for a [ColorGroup], the source creates the whole group and the other colors replace single colors after.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct GroupOverride {
+	/// Creates the group from this color instead of harmonizing it with the seed.
+	pub source: Option<HexColor>,
+	pub color: Option<HexColor>,
+	pub on_color: Option<HexColor>,
+	pub color_container: Option<HexColor>,
+	pub on_color_container: Option<HexColor>,
+}
+
+/// The colors of a [Theme] that are not part of a [ColorGroup].
+/// `bg` is not listed as it always follows the surface.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum NeutralRole {
+	Fg,
+	Outline,
+	OutlineWeak,
+	Shadow,
+	Surface,
+	SurfaceDim,
+	SurfaceBright,
+	SurfaceContainerLowest,
+	SurfaceContainerLow,
+	SurfaceContainer,
+	SurfaceContainerHigh,
+	SurfaceContainerHighest,
+	SurfaceVariant,
+	OnSurfaceVariant,
+	SurfaceTint,
+	InverseSurface,
+	InverseOnSurface,
+	InversePrimary,
+	Scrim,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Typography {
+	/// A font from `fonts/` in the assets without the extension, this replaces Roboto for regular text.
+	/// Fonts are loaded with the profile, so changing it needs a profile reload.
+	pub font: Option<String>,
+	/// Scales every text style.
+	pub scale: f32,
+}
+
+impl Default for Typography {
+	fn default() -> Self {
+		Typography {
+			font: None,
+			scale: 1.0,
+		}
+	}
+}
+
+/// A color written as `#rrggbb` or `#rrggbbaa`.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(pub Color32);
+
+impl HexColor {
+	fn argb(self) -> [u8; 4] {
+		[0xff, self.0.r(), self.0.g(), self.0.b()]
+	}
+}
+
+impl TryFrom<String> for HexColor {
+	type Error = String;
+
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		let digits = value
+			.strip_prefix('#')
+			.filter(|digits| (digits.len() == 6 || digits.len() == 8) && digits.is_ascii())
+			.ok_or_else(|| format!("Expected a color like #rrggbb or #rrggbbaa, got {value:?}"))?;
+		let mut channels = [0xff; 4];
+		for (i, channel) in channels.iter_mut().enumerate().take(digits.len() / 2) {
+			*channel = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16)
+				.map_err(|_| format!("{value:?} is not a hex color"))?;
+		}
+		let [r, g, b, a] = channels;
+		Ok(HexColor(Color32::from_rgba_unmultiplied(r, g, b, a)))
+	}
+}
+
+impl From<HexColor> for String {
+	fn from(color: HexColor) -> Self {
+		let [r, g, b, a] = color.0.to_srgba_unmultiplied();
+		if a == 0xff {
+			format!("#{r:02x}{g:02x}{b:02x}")
+		} else {
+			format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ThemeFileError {
+	EmptyName,
+	Rounding(f32),
+	FontScale(f32),
+}
+
+impl Display for ThemeFileError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ThemeFileError::EmptyName => write!(f, "The theme has no name"),
+			ThemeFileError::Rounding(rounding) => write!(f, "Rounding {rounding} is not between 0 and 100"),
+			ThemeFileError::FontScale(scale) => write!(f, "Font scale {scale} is not between 0.5 and 2"),
+		}
+	}
+}
+
+impl std::error::Error for ThemeFileError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hex_colors() {
+		let color = HexColor::try_from("#ff8000".to_string()).unwrap();
+		assert_eq!(color.0, Color32::from_rgb(0xff, 0x80, 0x00));
+		assert_eq!(String::from(color), "#ff8000");
+		assert!(HexColor::try_from("ff8000".to_string()).is_err());
+		assert!(HexColor::try_from("#ff80".to_string()).is_err());
+		assert!(HexColor::try_from("#gg8000".to_string()).is_err());
+	}
+
+	#[test]
+	fn overrides_are_exact() {
+		let brand = Color32::from_rgb(0x00, 0x33, 0x99);
+		let mut groups = BTreeMap::new();
+		groups.insert(ColorTag::Primary, GroupOverride {
+			color: Some(HexColor(brand)),
+			..GroupOverride::default()
+		});
+		let mut neutrals = BTreeMap::new();
+		neutrals.insert(NeutralRole::Surface, HexColor(Color32::from_gray(20)));
+		let file = ThemeFile {
+			name: "Brand".to_string(),
+			seed: HexColor(brand),
+			dark: ThemeOverrides { groups, neutrals },
+			light: ThemeOverrides::default(),
+			rounding: Some(8.0),
+			typography: Typography::default(),
+		};
+		assert_eq!(file.validate(), Ok(()));
+
+		let dark = file.build(true, Contrast::Standard, ColorVision::Normal);
+		assert_eq!(dark.primary.color, brand);
+		assert_eq!(dark.bg, Color32::from_gray(20));
+		assert_eq!(dark.rounding, 8.0);
+		let light = file.build(false, Contrast::Standard, ColorVision::Normal);
+		assert_ne!(light.primary.color, brand);
+	}
+}
@@ -4,43 +4,310 @@
 
 mod color;
 pub mod config;
+mod contrast;
+mod extract;
+mod file;
+mod schedule;
 mod theme;
+mod vision;
 
-use egui::Color32;
-use log::info;
-use ptya_animation::Lerp;
+use chrono::Local;
+use egui::{Color32, Id, Rounding};
+use log::{info, warn};
+use ptya_animation::{AnimationImpl, AnimationManager, AnimationRef, Lerp};
+use std::collections::BTreeMap;
 use std::ops::Deref;
 
-pub use crate::color::{ColorGroup, ColorTag};
-use crate::config::{ColorConfig, ThemeTag};
-pub use crate::theme::Theme;
+pub use crate::color::{ColorGroup, ColorTag, GroupTones};
+use crate::config::{ColorConfig, ColorVision, Contrast, ThemeSchedule, ThemeTag};
+pub use crate::contrast::{contrast_ratio, ensure_contrast, relative_luminance, ContrastIssue};
+pub use crate::extract::seed_from_image;
+pub use crate::file::{GroupOverride, HexColor, NeutralRole, ThemeFile, ThemeFileError, ThemeOverrides, Typography};
+pub use crate::schedule::{Daylight, ThemeSignals};
+pub use crate::theme::{Theme, DEFAULT_ROUNDING};
+use crate::vision::custom_group;
+pub use crate::vision::simulate;
+
+/// How long a theme change takes compared to other animations.
+/// Theme changes are slow on purpose, the whole screen flashing would be blinding in a dark cabin.
+const TRANSITION_SPEED: f32 = 4.0;
+/// How often the schedule is checked in seconds, changing a setting checks it right away.
+const SCHEDULE_INTERVAL: f64 = 1.0;
 
 pub struct ColorManager {
 	config: ColorConfig,
+	signals: ThemeSignals,
+	// If the dark theme is used right now, this can differ from the config because of the schedule.
+	dark: bool,
+	// The contrast used right now, this is high in glare even if the config asks for standard contrast.
+	contrast: Contrast,
+	files: Vec<ThemeFile>,
+	// Colors registered by apps by their source color.
+	custom: BTreeMap<&'static str, [u8; 3]>,
+	// Set when the target theme needs to be recreated or apps need to know about a new theme.
+	changed: bool,
+	// The animation time the schedule was last checked at, [None] checks it on the next tick.
+	checked: Option<f64>,
+	// Set while the theme fades to the target theme.
+	fading: bool,
+	// The theme currently on screen, this is somewhere between the old and the target theme while fading.
 	theme: Theme,
+	target: Theme,
 }
 
 impl ColorManager {
-	pub async fn new(config: ColorConfig) -> ColorManager {
-		info!("Created color manager");
-
-		ColorManager {
-			theme: Theme::new(
-				match config.theme {
-					ThemeTag::Pitaya => [0xe5, 0x4c, 0x64],
-					ThemeTag::Custom(rgb) => rgb,
-				},
-				config.dark_mode,
-			)
-			.await,
+	/// Creates the color manager, `files` are the theme files that [ThemeTag::File] can select.
+	pub async fn new(config: ColorConfig, files: Vec<ThemeFile>) -> ColorManager {
+		info!("Created color manager with {} theme files", files.len());
+
+		let signals = ThemeSignals::default();
+		let dark = schedule::is_dark(&config, &signals, Local::now(), config.dark_mode);
+		let contrast = Self::effective_contrast(&config, &signals);
+		let mut manager = ColorManager {
 			config,
+			signals,
+			dark,
+			contrast,
+			files,
+			custom: BTreeMap::new(),
+			changed: false,
+			checked: None,
+			fading: false,
+			theme: Theme::default(),
+			target: Theme::default(),
+		};
+		manager.target = manager.build_theme();
+		manager.theme = manager.target.clone();
+		manager
+	}
+
+	/// Creates the theme for the config, any colors that end up too close to their background get fixed.
+	/// Colors set by a theme file are kept as they are, poor contrast is only logged for those.
+	fn build_theme(&self) -> Theme {
+		let file = match &self.config.theme {
+			ThemeTag::File(name) => {
+				let file = self.files.iter().find(|file| &file.name == name);
+				if file.is_none() {
+					warn!("Could not find the theme {name:?}, using the default theme");
+				}
+				file
+			}
+			_ => None,
+		};
+
+		let (seed, mut theme) = match (file, &self.config.theme) {
+			(Some(file), _) => {
+				let seed = file.seed.0;
+				let seed = [seed.r(), seed.g(), seed.b()];
+				(seed, file.build(self.dark, self.contrast, self.config.color_vision))
+			}
+			(None, tag) => {
+				let seed = match tag {
+					ThemeTag::Custom(rgb) => *rgb,
+					_ => [0xe5, 0x4c, 0x64],
+				};
+				(seed, self.seed_theme(seed))
+			}
+		};
+
+		self.add_custom(&mut theme, seed);
+		if let Some(file) = file {
+			for issue in theme.check_contrast(self.contrast) {
+				warn!(
+					"Theme {:?} has a contrast of {:.2} for {}, {:.1} is needed",
+					file.name, issue.ratio, issue.pair, issue.required
+				);
+			}
+		}
+		theme
+	}
+
+	/// Creates a theme from a seed for the current dark mode, contrast and color vision.
+	/// Seeds can be any color, so the tones are moved apart when they end up too close to their background.
+	fn seed_theme(&self, seed: [u8; 3]) -> Theme {
+		let mut theme = Theme::with_vision(seed, self.dark, self.contrast, self.config.color_vision);
+		let issues = theme.check_contrast(self.contrast);
+		if !issues.is_empty() {
+			info!("Fixing the contrast of {} colors", issues.len());
+			theme.enforce_contrast(self.contrast);
+		}
+		theme
+	}
+
+	/// Adds the colors registered by apps to a theme made from a seed.
+	/// They are not part of the seed or a theme file, so they are fixed like in any other theme.
+	fn add_custom(&self, theme: &mut Theme, seed: [u8; 3]) {
+		let source = [0xff, seed[0], seed[1], seed[2]];
+		let tones = GroupTones::new(self.dark, self.contrast);
+		for (name, [r, g, b]) in &self.custom {
+			let mut group = custom_group(source, [0xff, *r, *g, *b], tones, self.config.color_vision);
+			group.enforce_contrast(self.contrast);
+			theme.custom.insert(name, group);
+		}
+	}
+
+	/// Registers a named color for apps, it is harmonized to the seed and regenerated on every theme change.
+	/// Look it up with [ColorTag::Custom] like any other color.
+	/// Names are shared by all apps, a name that is already registered with another color keeps its first color.
+	pub fn register_color(&mut self, name: &'static str, rgb: [u8; 3]) {
+		match self.custom.get(name) {
+			Some(existing) if *existing == rgb => {}
+			Some(existing) => warn!("The color {name:?} is already registered as {existing:?}, ignoring {rgb:?}"),
+			None => {
+				self.custom.insert(name, rgb);
+				self.target = self.build_theme();
+				self.changed = true;
+			}
+		}
+	}
+
+	fn effective_contrast(config: &ColorConfig, signals: &ThemeSignals) -> Contrast {
+		match signals.light_sensor {
+			Some(lux) if lux > config.light_sensor.glare_above => Contrast::High,
+			_ => config.contrast,
+		}
+	}
+
+	/// Follows the schedule and moves the theme towards the target theme, this needs to run after the animation manager ticked.
+	/// Returns true when the target theme changed.
+	pub fn tick(&mut self, animation: &AnimationManager) -> bool {
+		let time = animation.time();
+		if self.checked.is_none_or(|checked| time - checked >= SCHEDULE_INTERVAL) {
+			self.checked = Some(time);
+			let dark = schedule::is_dark(&self.config, &self.signals, Local::now(), self.dark);
+			let contrast = Self::effective_contrast(&self.config, &self.signals);
+			if dark != self.dark || contrast != self.contrast {
+				info!(
+					"Switching to the {} theme with {:?} contrast",
+					if dark { "dark" } else { "light" },
+					contrast
+				);
+				self.dark = dark;
+				self.contrast = contrast;
+				self.target = self.build_theme();
+				self.changed = true;
+			}
+		}
+
+		// Themes are large, so they are only blended while a fade is running.
+		if self.changed || self.fading {
+			let mut transition = animation.get_ref_or(AnimationRef::new(Id::new("pitaya@theme")), || {
+				AnimationImpl::simple(self.theme.clone())
+			});
+			if self.changed {
+				// Starting from the current value keeps a theme change smooth even if the last one is still fading.
+				transition.retarget_with_speed(self.target.clone(), TRANSITION_SPEED);
+			}
+			self.theme = transition.get_value();
+			self.fading = !transition.is_finished();
 		}
+
+		std::mem::replace(&mut self.changed, false)
+	}
+
+	pub fn config(&self) -> &ColorConfig {
+		&self.config
+	}
+
+	/// Checks if the dark theme is being used, this follows the schedule.
+	pub fn is_dark(&self) -> bool {
+		self.dark
+	}
+
+	/// Sets the dark mode used without a schedule, the colors fade over to the new theme.
+	pub fn set_dark_mode(&mut self, dark_mode: bool) {
+		self.config.dark_mode = dark_mode;
+		self.checked = None;
+	}
+
+	/// The contrast used right now, high contrast is forced in glare.
+	pub fn contrast(&self) -> Contrast {
+		self.contrast
+	}
+
+	/// Sets the contrast, the colors fade over to the new theme.
+	pub fn set_contrast(&mut self, contrast: Contrast) {
+		self.config.contrast = contrast;
+		self.checked = None;
+	}
+
+	/// Moves the semantic colors apart for a color vision deficiency, the colors fade over to the new theme.
+	pub fn set_color_vision(&mut self, vision: ColorVision) {
+		self.config.color_vision = vision;
+		self.target = self.build_theme();
+		self.changed = true;
+	}
+
+	/// Checks if components should add icons to colors that carry meaning, like red for decline.
+	pub fn icon_cues(&self) -> bool {
+		self.config.icon_cues || self.config.color_vision != ColorVision::Normal
+	}
+
+	pub fn set_icon_cues(&mut self, icon_cues: bool) {
+		self.config.icon_cues = icon_cues;
+	}
+
+	pub fn set_schedule(&mut self, schedule: ThemeSchedule) {
+		self.config.schedule = schedule;
+		self.checked = None;
+	}
+
+	/// Updates the last known location of the vehicle, used for sunrise and sunset.
+	pub fn set_location(&mut self, latitude: f64, longitude: f64) {
+		self.config.location = Some([latitude, longitude]);
+		self.checked = None;
+	}
+
+	/// Updates the ambient light in lux, [None] if the sensor is not available.
+	/// This can be called every frame, the theme follows it within a second.
+	pub fn set_light_sensor(&mut self, lux: Option<f32>) {
+		self.signals.light_sensor = lux;
 	}
 
+	/// Updates the headlight signal, the dark theme is used while the headlights are on.
+	pub fn set_headlights(&mut self, headlights: Option<bool>) {
+		self.signals.headlights = headlights;
+		self.checked = None;
+	}
+
+	/// Changes the seed of the theme, the colors fade over to the new theme.
+	pub fn set_theme_tag(&mut self, tag: ThemeTag) {
+		self.config.theme = tag;
+		self.target = self.build_theme();
+		self.changed = true;
+	}
+
+	/// The theme files that can be selected with [ThemeTag::File].
+	pub fn theme_files(&self) -> &[ThemeFile] {
+		&self.files
+	}
+
+	/// Fades over to any theme, this gets replaced when the schedule switches between dark and light.
+	pub fn set_theme(&mut self, theme: Theme) {
+		self.target = theme;
+		self.changed = true;
+	}
+
+	/// Creates a theme from an image for an app to use in its own panel.
+	/// It is made like the main theme, so it follows the current dark mode, contrast and color vision
+	/// and has the colors registered by apps.
+	pub fn theme_from_image(&self, pixels: &[Color32]) -> Theme {
+		let seed = seed_from_image(pixels);
+		let mut theme = self.seed_theme(seed);
+		self.add_custom(&mut theme, seed);
+		theme
+	}
+
+	/// The theme as it is currently drawn.
 	pub fn theme(&self) -> &Theme {
 		&self.theme
 	}
 
+	/// The theme that is being faded to, this is the same as [ColorManager::theme] when no fade is running.
+	pub fn target_theme(&self) -> &Theme {
+		&self.target
+	}
+
 	pub fn new_state(&self) -> ColorState {
 		ColorState {
 			level: 0.0,
@@ -49,6 +316,11 @@ impl ColorManager {
 	}
 }
 
+/// Levels of common components, [ColorState::bg] maps whole levels onto the surface containers.
+pub const LEVEL_CARD: f32 = 1.0;
+pub const LEVEL_MENU: f32 = 2.0;
+pub const LEVEL_DIALOG: f32 = 3.0;
+
 #[derive(Copy, Clone)]
 pub struct ColorState<'a> {
 	pub level: f32,
@@ -63,9 +335,36 @@ impl<'a> ColorState<'a> {
 		}
 	}
 
+	/// Moves to an absolute level, like [LEVEL_DIALOG].
+	pub fn at_level(self, level: f32) -> ColorState<'a> {
+		ColorState {
+			level,
+			theme: self.theme,
+		}
+	}
+
+	/// The corner radius of components in the current theme.
+	pub fn rounding(&self) -> Rounding {
+		Rounding::same(self.theme.rounding)
+	}
+
+	pub fn card(self) -> ColorState<'a> {
+		self.at_level(LEVEL_CARD)
+	}
+
+	pub fn dialog(self) -> ColorState<'a> {
+		self.at_level(LEVEL_DIALOG)
+	}
+
+	/// The surface for the current level, level 0 is the surface and every level above moves up a surface container.
+	/// Levels in between blend the containers around them.
 	#[inline(always)]
 	pub fn bg(&self) -> Color32 {
-		self.group_bg(&self.theme.primary)
+		let containers = self.theme.surface_containers();
+		let level = self.level.clamp(0.0, (containers.len() - 1) as f32);
+		let below = level.floor() as usize;
+		let above = (below + 1).min(containers.len() - 1);
+		containers[below].lerp(&containers[above], level.fract())
 	}
 
 	#[inline(always)]
@@ -78,9 +377,11 @@ impl<'a> ColorState<'a> {
 		self.color_bg(group.color)
 	}
 
+	/// The surface tinted towards an accent, higher levels get more of the accent.
+	/// The tint is the elevation here, so it starts from the level 0 surface instead of [ColorState::bg].
 	#[inline(always)]
 	pub fn color_bg(&self, accent: Color32) -> Color32 {
-		self.theme.bg.lerp(&accent, self.level / 14.0)
+		self.theme.surface.lerp(&accent, self.level / 14.0)
 	}
 }
 
@@ -91,3 +392,107 @@ impl<'a> Deref for ColorState<'a> {
 		self.theme
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ptya_animation::config::AnimationConfig;
+	use ptya_animation::Clock;
+
+	#[test]
+	fn levels_blend_the_surface_containers() {
+		let theme = Theme::from_seed([0xe5, 0x4c, 0x64], true);
+		let containers = theme.surface_containers();
+		let state = |level| ColorState { level, theme: &theme };
+
+		assert_eq!(state(0.0).bg(), containers[0]);
+		assert_eq!(state(LEVEL_DIALOG).bg(), containers[3]);
+		assert_eq!(state(1.5).bg(), containers[1].lerp(&containers[2], 0.5));
+		assert_eq!(state(-2.0).bg(), containers[0]);
+		assert_eq!(state(20.0).bg(), containers[4]);
+	}
+
+	#[tokio::test]
+	async fn custom_colors_follow_the_theme() {
+		let mut manager = ColorManager::new(ColorConfig::default(), vec![]).await;
+		manager.register_color("maps", [0x20, 0xa0, 0x40]);
+		let before = manager.target_theme().get(ColorTag::Custom("maps")).clone();
+		assert!(before != manager.target_theme().primary);
+
+		manager.set_theme_tag(ThemeTag::Custom([0x30, 0x60, 0xff]));
+		let mut theme = manager.target_theme().clone();
+		assert!(*theme.get(ColorTag::Custom("maps")) != before);
+		assert!(theme.check_contrast(manager.contrast()).is_empty());
+
+		// Missing colors fall back to the primary color without being added.
+		let primary = theme.primary.clone();
+		assert!(*theme.get_mut(ColorTag::Custom("missing")) == primary);
+		assert_eq!(theme.custom.len(), 1);
+
+		// Another app can not recolor it.
+		manager.register_color("maps", [0xff, 0x00, 0xff]);
+		assert!(*manager.target_theme().get(ColorTag::Custom("maps")) == *theme.get(ColorTag::Custom("maps")));
+
+		let image = manager.theme_from_image(&[Color32::from_rgb(0x30, 0x60, 0xc0); 64]);
+		assert!(image.custom.contains_key("maps"));
+	}
+
+	#[tokio::test]
+	async fn image_themes_follow_the_contrast() {
+		let config = ColorConfig {
+			contrast: Contrast::High,
+			..Default::default()
+		};
+		let manager = ColorManager::new(config, vec![]).await;
+		// A pale image gives a weak seed.
+		let pixels = [Color32::from_rgb(0xf0, 0xe8, 0xd0); 64];
+		let theme = manager.theme_from_image(&pixels);
+		assert!(theme.check_contrast(Contrast::High).is_empty());
+		assert!(theme.fg == Theme::with_contrast(seed_from_image(&pixels), manager.is_dark(), Contrast::High).fg);
+	}
+
+	#[tokio::test]
+	async fn image_themes_follow_the_color_vision() {
+		let config = ColorConfig {
+			color_vision: ColorVision::Deutan,
+			..Default::default()
+		};
+		let manager = ColorManager::new(config, vec![]).await;
+		let pixels = [Color32::from_rgb(0x30, 0x60, 0xc0); 64];
+		let theme = manager.theme_from_image(&pixels);
+		// The semantic colors are moved apart instead of harmonized to the image.
+		let normal = Theme::from_seed(seed_from_image(&pixels), manager.is_dark());
+		assert!(theme.red != normal.red && theme.green != normal.green);
+	}
+
+	#[tokio::test]
+	async fn theme_changes_fade_and_settle() {
+		let mut manager = ColorManager::new(ColorConfig::default(), vec![]).await;
+		let animation = AnimationManager::with_clock(AnimationConfig::default(), Clock::manual());
+		assert!(!manager.tick(&animation));
+
+		manager.set_theme_tag(ThemeTag::Custom([0x30, 0x60, 0xff]));
+		assert!(manager.tick(&animation));
+		animation.advance(0.1);
+		manager.tick(&animation);
+		assert!(manager.theme() != manager.target_theme());
+
+		for _ in 0..20 {
+			animation.advance(0.1);
+			assert!(!manager.tick(&animation));
+		}
+		assert!(manager.theme() == manager.target_theme());
+		assert!(!manager.fading);
+	}
+
+	#[test]
+	fn tints_are_applied_once() {
+		let theme = Theme::from_seed([0xe5, 0x4c, 0x64], true);
+		let accent = theme.primary.color;
+		assert_eq!(ColorState { level: 0.0, theme: &theme }.color_bg(accent), theme.surface);
+		assert_eq!(
+			ColorState { level: 7.0, theme: &theme }.color_bg(accent),
+			theme.surface.lerp(&accent, 0.5)
+		);
+	}
+}
@@ -0,0 +1,176 @@
+use crate::config::{ColorConfig, ThemeSchedule};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+/// Signals from the vehicle that override the schedule, [None] if the vehicle does not have them.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct ThemeSignals {
+	/// Ambient light in lux.
+	pub light_sensor: Option<f32>,
+	pub headlights: Option<bool>,
+}
+
+/// Decides if the dark theme should be used.
+/// Headlights win over the light sensor which wins over the schedule,
+/// `current` is kept while the light sensor is between its thresholds.
+pub fn is_dark(config: &ColorConfig, signals: &ThemeSignals, now: DateTime<Local>, current: bool) -> bool {
+	if signals.headlights == Some(true) {
+		return true;
+	}
+
+	if let Some(lux) = signals.light_sensor {
+		return if lux < config.light_sensor.dark_below {
+			true
+		} else if lux > config.light_sensor.light_above {
+			false
+		} else {
+			current
+		};
+	}
+
+	match config.schedule {
+		ThemeSchedule::Off => config.dark_mode,
+		ThemeSchedule::Times { light, dark } => {
+			let time = now.time();
+			if light <= dark {
+				!(light <= time && time < dark)
+			} else {
+				dark <= time && time < light
+			}
+		}
+		ThemeSchedule::Sun => match config.location {
+			Some([latitude, longitude]) => {
+				match Daylight::new(now.date_naive(), latitude, longitude) {
+					Daylight::Sun { sunrise, sunset } => {
+						let now = now.with_timezone(&Utc);
+						!(sunrise <= now && now < sunset)
+					}
+					Daylight::PolarDay => false,
+					Daylight::PolarNight => true,
+				}
+			}
+			None => config.dark_mode,
+		},
+	}
+}
+
+/// When the sun is up on a day.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Daylight {
+	Sun {
+		sunrise: DateTime<Utc>,
+		sunset: DateTime<Utc>,
+	},
+	/// The sun does not set.
+	PolarDay,
+	/// The sun does not rise.
+	PolarNight,
+}
+
+impl Daylight {
+	/// Calculates sunrise and sunset with the sunrise equation, this is accurate to a minute or two.
+	/// Longitude is positive towards the east.
+	pub fn new(date: NaiveDate, latitude: f64, longitude: f64) -> Daylight {
+		const J2000: f64 = 2451545.0;
+		const UNIX_EPOCH: f64 = 2440587.5;
+
+		let days = date
+			.signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+			.num_days() as f64;
+		let n = (days + UNIX_EPOCH - J2000 + 0.0008).ceil();
+
+		// Mean solar time, solar mean anomaly and the equation of the center.
+		let mean_time = n - longitude / 360.0;
+		let anomaly = (357.5291 + 0.98560028 * mean_time).rem_euclid(360.0).to_radians();
+		let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+		let ecliptic = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+			.rem_euclid(360.0)
+			.to_radians();
+		let transit = J2000 + mean_time + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+
+		let declination = (ecliptic.sin() * 23.4397f64.to_radians().sin()).asin();
+		let latitude = latitude.to_radians();
+		let hour_angle = ((-0.833f64).to_radians().sin() - latitude.sin() * declination.sin())
+			/ (latitude.cos() * declination.cos());
+
+		if hour_angle < -1.0 {
+			return Daylight::PolarDay;
+		}
+		if hour_angle > 1.0 {
+			return Daylight::PolarNight;
+		}
+
+		let hour_angle = hour_angle.acos().to_degrees() / 360.0;
+		let to_utc = |julian: f64| {
+			let seconds = (julian - UNIX_EPOCH) * 86400.0;
+			DateTime::<Utc>::from_timestamp(seconds as i64, 0).unwrap_or_default()
+		};
+		Daylight::Sun {
+			sunrise: to_utc(transit - hour_angle),
+			sunset: to_utc(transit + hour_angle),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::{NaiveTime, TimeZone};
+
+	#[test]
+	fn sunrise_and_sunset() {
+		// Stockholm on midsummer, sunrise is around 01:30 and sunset around 20:08 UTC.
+		let date = NaiveDate::from_ymd_opt(2022, 6, 21).unwrap();
+		match Daylight::new(date, 59.33, 18.07) {
+			Daylight::Sun { sunrise, sunset } => {
+				let expected_rise = Utc.with_ymd_and_hms(2022, 6, 21, 1, 30, 0).unwrap();
+				let expected_set = Utc.with_ymd_and_hms(2022, 6, 21, 20, 8, 0).unwrap();
+				assert!((sunrise - expected_rise).num_minutes().abs() <= 5, "{sunrise}");
+				assert!((sunset - expected_set).num_minutes().abs() <= 5, "{sunset}");
+			}
+			daylight => panic!("Expected a sunrise, got {daylight:?}"),
+		}
+
+		// Svalbard.
+		assert_eq!(Daylight::new(date, 78.2, 15.6), Daylight::PolarDay);
+		let winter = NaiveDate::from_ymd_opt(2022, 12, 21).unwrap();
+		assert_eq!(Daylight::new(winter, 78.2, 15.6), Daylight::PolarNight);
+	}
+
+	#[test]
+	fn signals_override_schedule() {
+		let config = ColorConfig {
+			dark_mode: false,
+			schedule: ThemeSchedule::Times {
+				light: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+				dark: NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+			},
+			..ColorConfig::default()
+		};
+		let noon = Local.with_ymd_and_hms(2022, 6, 21, 12, 0, 0).unwrap();
+		let night = Local.with_ymd_and_hms(2022, 6, 21, 23, 0, 0).unwrap();
+		let none = ThemeSignals::default();
+
+		assert!(!is_dark(&config, &none, noon, true));
+		assert!(is_dark(&config, &none, night, false));
+
+		let tunnel = ThemeSignals {
+			light_sensor: Some(10.0),
+			..ThemeSignals::default()
+		};
+		assert!(is_dark(&config, &tunnel, noon, false));
+
+		// Between the thresholds the current theme stays.
+		let dusk = ThemeSignals {
+			light_sensor: Some(200.0),
+			..ThemeSignals::default()
+		};
+		assert!(is_dark(&config, &dusk, noon, true));
+		assert!(!is_dark(&config, &dusk, noon, false));
+
+		let headlights = ThemeSignals {
+			light_sensor: Some(1000.0),
+			headlights: Some(true),
+		};
+		assert!(is_dark(&config, &headlights, noon, false));
+	}
+}
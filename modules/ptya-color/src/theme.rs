@@ -1,13 +1,22 @@
 use egui::Color32;
+use std::collections::BTreeMap;
 use material_color_utilities_rs::palettes::core::CorePalette;
-use crate::color::{ColorGroup, ColorTag};
+use material_color_utilities_rs::palettes::tonal::TonalPalette;
+use ptya_animation::Lerp;
+use crate::color::{ColorGroup, ColorTag, GroupTones};
+use crate::config::{ColorVision, Contrast};
+use crate::extract::seed_from_image;
+use crate::file::NeutralRole;
+use crate::vision::semantic_colors;
 
-#[derive(Default, Clone)]
+/// A material 3 color scheme, with some extra semantic colors.
+#[derive(Default, Clone, PartialEq, Lerp)]
 pub struct Theme {
     // Accents
     pub primary: ColorGroup,
     pub secondary: ColorGroup,
     pub tertiary: ColorGroup,
+    pub error: ColorGroup,
     // Colors
     pub red: ColorGroup,
     pub orange: ColorGroup,
@@ -15,37 +24,177 @@ pub struct Theme {
     pub green: ColorGroup,
     pub blue: ColorGroup,
     // Neutral
+    /// The background of the whole screen, this is the same as the surface.
     pub bg: Color32,
+    /// Text and icons on any surface.
     pub fg: Color32,
     pub outline: Color32,
     pub outline_weak: Color32,
     pub shadow: Color32,
+    // Surfaces, see [ColorState::bg](crate::ColorState::bg) for how they map to elevation.
+    pub surface: Color32,
+    pub surface_dim: Color32,
+    pub surface_bright: Color32,
+    pub surface_container_lowest: Color32,
+    pub surface_container_low: Color32,
+    pub surface_container: Color32,
+    pub surface_container_high: Color32,
+    pub surface_container_highest: Color32,
+    pub surface_variant: Color32,
+    pub on_surface_variant: Color32,
+    /// Tints surfaces towards the primary color.
+    pub surface_tint: Color32,
+    // Inverse, for things like snackbars that stand out from the surface.
+    pub inverse_surface: Color32,
+    pub inverse_on_surface: Color32,
+    pub inverse_primary: Color32,
+    /// Darkens everything behind modal dialogs and sheets.
+    pub scrim: Color32,
+    /// The corner radius of components, see [ColorState::rounding](crate::ColorState::rounding).
+    pub rounding: f32,
+    /// Colors registered by apps, harmonized to the seed like the other colors.
+    pub custom: BTreeMap<&'static str, ColorGroup>,
 }
 
+/// The corner radius of themes that do not set one.
+pub const DEFAULT_ROUNDING: f32 = 25.0;
+
 impl Theme {
     pub async fn new(rgb: [u8; 3], dark_mode: bool) -> Theme {
+        Self::from_seed(rgb, dark_mode)
+    }
+
+    /// Creates a theme from a seed color without waiting, used when the theme changes mid frame.
+    pub fn from_seed(rgb: [u8; 3], dark_mode: bool) -> Theme {
+        Self::with_contrast(rgb, dark_mode, Contrast::Standard)
+    }
+
+    /// Creates a theme with a contrast level, this only picks tones and does not check them.
+    /// Use [Theme::enforce_contrast] for seeds that might not be readable.
+    pub fn with_contrast(rgb: [u8; 3], dark_mode: bool, contrast: Contrast) -> Theme {
+        Self::with_vision(rgb, dark_mode, contrast, ColorVision::Normal)
+    }
+
+    /// Creates a theme with a contrast level whose semantic colors stay apart for a color vision deficiency.
+    pub fn with_vision(rgb: [u8; 3], dark_mode: bool, contrast: Contrast, vision: ColorVision) -> Theme {
         let source_argb = [0xff, rgb[0], rgb[1], rgb[2]];
         let mut palette = CorePalette::new(source_argb, false);
 
-        let bg = if dark_mode { 10 } else { 99 };
-        let fg = if dark_mode { 90 } else { 10 };
-        let outline = if dark_mode { 60 } else { 50 };
-        let outline_weak = if dark_mode { 30 } else { 80 };
+        let high = contrast == Contrast::High;
+        let tone = |palette: &mut TonalPalette, dark: u8, light: u8| {
+            color32_from_argb(palette.tone(if dark_mode { dark } else { light }))
+        };
+        let contrast_tone = |palette: &mut TonalPalette, standard: (u8, u8), high_contrast: (u8, u8)| {
+            let (dark, light) = if high { high_contrast } else { standard };
+            color32_from_argb(palette.tone(if dark_mode { dark } else { light }))
+        };
 
+        let tones = GroupTones::new(dark_mode, contrast);
+        let primary = ColorGroup::from_tones(&mut palette.a1, tones);
+        let surface = tone(&mut palette.n1, 6, 98);
+        let [red, orange, yellow, green, blue] = match semantic_colors(vision) {
+            Some(colors) => colors.map(|color| ColorGroup::new_source(color, tones)),
+            None => [
+                [0xff, 0xff, 0x00, 0x00],
+                [0xff, 0xff, 0x80, 0x00],
+                [0xff, 0xff, 0xff, 0x00],
+                [0xff, 0x00, 0xff, 0x00],
+                [0xff, 0x00, 0xff, 0xff],
+            ]
+            .map(|color| ColorGroup::new_harmonized(source_argb, color, tones)),
+        };
         Theme {
-            primary: ColorGroup::new_tonal(&mut palette.a1, dark_mode),
-            secondary: ColorGroup::new_tonal(&mut palette.a2, dark_mode),
-            tertiary: ColorGroup::new_tonal(&mut palette.a3, dark_mode),
-            red: ColorGroup::new_custom(source_argb, [0xff, 0xff, 0x00, 0x00], dark_mode).await,
-            orange: ColorGroup::new_custom(source_argb, [0xff, 0xff, 0x80, 0x00], dark_mode).await,
-            yellow: ColorGroup::new_custom(source_argb, [0xff, 0xff, 0xff, 0x00], dark_mode).await,
-            green: ColorGroup::new_custom(source_argb, [0xff, 0x00, 0xff, 0x00], dark_mode).await,
-            blue: ColorGroup::new_custom(source_argb, [0xff, 0x00, 0xff, 0xff], dark_mode).await,
-            bg: color32_from_argb(palette.n1.tone(bg)),
-            fg: color32_from_argb(palette.n1.tone(fg)),
-            outline: color32_from_argb(palette.n2.tone(outline)),
-            outline_weak: color32_from_argb(palette.n2.tone(outline_weak)),
+            secondary: ColorGroup::from_tones(&mut palette.a2, tones),
+            tertiary: ColorGroup::from_tones(&mut palette.a3, tones),
+            error: ColorGroup::from_tones(&mut palette.error, tones),
+            red,
+            orange,
+            yellow,
+            green,
+            blue,
+            bg: surface,
+            fg: contrast_tone(&mut palette.n1, (90, 10), (100, 0)),
+            outline: contrast_tone(&mut palette.n2, (60, 50), (80, 30)),
+            outline_weak: contrast_tone(&mut palette.n2, (30, 80), (50, 60)),
             shadow: Color32::BLACK.linear_multiply(0.1),
+            surface,
+            surface_dim: tone(&mut palette.n1, 6, 87),
+            surface_bright: tone(&mut palette.n1, 24, 98),
+            surface_container_lowest: tone(&mut palette.n1, 4, 100),
+            surface_container_low: tone(&mut palette.n1, 10, 96),
+            surface_container: tone(&mut palette.n1, 12, 94),
+            surface_container_high: tone(&mut palette.n1, 17, 92),
+            surface_container_highest: tone(&mut palette.n1, 22, 90),
+            surface_variant: tone(&mut palette.n2, 30, 90),
+            on_surface_variant: contrast_tone(&mut palette.n2, (80, 30), (95, 10)),
+            surface_tint: primary.color,
+            inverse_surface: tone(&mut palette.n1, 90, 20),
+            inverse_on_surface: contrast_tone(&mut palette.n1, (20, 95), (10, 100)),
+            inverse_primary: tone(&mut palette.a1, 40, 80),
+            scrim: Color32::BLACK.linear_multiply(0.32),
+            rounding: DEFAULT_ROUNDING,
+            custom: BTreeMap::new(),
+            primary,
+        }
+    }
+
+    /// The surface containers from the lowest to the highest elevation.
+    pub fn surface_containers(&self) -> [Color32; 5] {
+        [
+            self.surface,
+            self.surface_container_low,
+            self.surface_container,
+            self.surface_container_high,
+            self.surface_container_highest,
+        ]
+    }
+
+    /// Creates a theme that takes on the colors of an image, like the art of the album that is playing.
+    /// Apps can draw their panel with it through `Pui::child`.
+    pub fn from_image(pixels: &[Color32], dark_mode: bool) -> Theme {
+        Self::from_seed(seed_from_image(pixels), dark_mode)
+    }
+
+    pub fn get_mut(&mut self, color: ColorTag) -> &mut ColorGroup {
+        match color {
+            ColorTag::Primary => &mut self.primary,
+            ColorTag::Secondary => &mut self.secondary,
+            ColorTag::Tertiary => &mut self.tertiary,
+            ColorTag::Error => &mut self.error,
+            ColorTag::Red => &mut self.red,
+            ColorTag::Orange => &mut self.orange,
+            ColorTag::Yellow => &mut self.yellow,
+            ColorTag::Green => &mut self.green,
+            ColorTag::Blue => &mut self.blue,
+            // Like [Theme::get] this falls back to the primary color, custom colors are only added by the color manager.
+            ColorTag::Custom(name) => match self.custom.get_mut(name) {
+                Some(group) => group,
+                None => &mut self.primary,
+            },
+        }
+    }
+
+    pub fn neutral_mut(&mut self, role: NeutralRole) -> &mut Color32 {
+        match role {
+            NeutralRole::Fg => &mut self.fg,
+            NeutralRole::Outline => &mut self.outline,
+            NeutralRole::OutlineWeak => &mut self.outline_weak,
+            NeutralRole::Shadow => &mut self.shadow,
+            NeutralRole::Surface => &mut self.surface,
+            NeutralRole::SurfaceDim => &mut self.surface_dim,
+            NeutralRole::SurfaceBright => &mut self.surface_bright,
+            NeutralRole::SurfaceContainerLowest => &mut self.surface_container_lowest,
+            NeutralRole::SurfaceContainerLow => &mut self.surface_container_low,
+            NeutralRole::SurfaceContainer => &mut self.surface_container,
+            NeutralRole::SurfaceContainerHigh => &mut self.surface_container_high,
+            NeutralRole::SurfaceContainerHighest => &mut self.surface_container_highest,
+            NeutralRole::SurfaceVariant => &mut self.surface_variant,
+            NeutralRole::OnSurfaceVariant => &mut self.on_surface_variant,
+            NeutralRole::SurfaceTint => &mut self.surface_tint,
+            NeutralRole::InverseSurface => &mut self.inverse_surface,
+            NeutralRole::InverseOnSurface => &mut self.inverse_on_surface,
+            NeutralRole::InversePrimary => &mut self.inverse_primary,
+            NeutralRole::Scrim => &mut self.scrim,
         }
     }
 
@@ -54,11 +203,13 @@ impl Theme {
             ColorTag::Primary => &self.primary,
             ColorTag::Secondary => &self.secondary,
             ColorTag::Tertiary => &self.tertiary,
+            ColorTag::Error => &self.error,
             ColorTag::Red => &self.red,
             ColorTag::Orange => &self.orange,
             ColorTag::Yellow => &self.yellow,
             ColorTag::Green => &self.green,
             ColorTag::Blue => &self.blue,
+            ColorTag::Custom(name) => self.custom.get(name).unwrap_or(&self.primary),
         }
     }
 }
@@ -0,0 +1,101 @@
+use crate::color::{ColorGroup, GroupTones};
+use crate::config::ColorVision;
+use egui::{Color32, Rgba};
+
+/// Simulates how a color looks with a color vision deficiency, using the full severity models by Machado et al. 2009.
+/// This is meant for checking palettes and previews, not for drawing.
+pub fn simulate(color: Color32, vision: ColorVision) -> Color32 {
+	let matrix = match vision {
+		ColorVision::Normal => return color,
+		ColorVision::Protan => [
+			[0.152286, 1.052583, -0.204868],
+			[0.114503, 0.786281, 0.099216],
+			[-0.003882, -0.048116, 1.051998],
+		],
+		ColorVision::Deutan => [
+			[0.367322, 0.860646, -0.227968],
+			[0.280085, 0.672501, 0.047413],
+			[-0.011820, 0.042940, 0.968881],
+		],
+		ColorVision::Tritan => [
+			[1.255528, -0.076749, -0.178779],
+			[-0.078411, 0.930809, 0.147602],
+			[0.004733, 0.691367, 0.303900],
+		],
+	};
+
+	// The models work on linear light.
+	let linear = Rgba::from(color);
+	let rgb = [linear.r(), linear.g(), linear.b()];
+	let [r, g, b] = matrix.map(|row| {
+		(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, linear.a())
+	});
+	Color32::from(Rgba::from_rgba_premultiplied(r, g, b, linear.a()))
+}
+
+/// The source colors for red, orange, yellow, green and blue that stay apart for a color vision deficiency.
+/// These are based on the Okabe-Ito palette. [None] means the colors get harmonized with the seed as usual,
+/// harmonizing would move the hues back together.
+pub(crate) fn semantic_colors(vision: ColorVision) -> Option<[[u8; 4]; 5]> {
+	match vision {
+		ColorVision::Normal => None,
+		// Accepting turns blue, declining a strong orange red, these differ in lightness as well as hue.
+		ColorVision::Protan | ColorVision::Deutan => Some([
+			[0xff, 0xd5, 0x5e, 0x00],
+			[0xff, 0xe6, 0x9f, 0x00],
+			[0xff, 0xf0, 0xe4, 0x42],
+			[0xff, 0x00, 0x72, 0xb2],
+			[0xff, 0x56, 0xb4, 0xe9],
+		]),
+		// Red and green are fine, but green must not drift towards blue.
+		ColorVision::Tritan => Some([
+			[0xff, 0xe0, 0x30, 0x30],
+			[0xff, 0xe6, 0x9f, 0x00],
+			[0xff, 0xf0, 0xe4, 0x42],
+			[0xff, 0x00, 0x9e, 0x73],
+			[0xff, 0x00, 0x72, 0xb2],
+		]),
+	}
+}
+
+/// Creates the group for a color registered by an app, like [semantic_colors] it is only harmonized with normal vision.
+pub(crate) fn custom_group(source: [u8; 4], color: [u8; 4], tones: GroupTones, vision: ColorVision) -> ColorGroup {
+	match vision {
+		ColorVision::Normal => ColorGroup::new_harmonized(source, color, tones),
+		_ => ColorGroup::new_source(color, tones),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::Contrast;
+	use crate::Theme;
+	use material_color_utilities_rs::util::color::lab_from_argb;
+
+	fn distance(a: Color32, b: Color32) -> f64 {
+		let a = lab_from_argb([0xff, a.r(), a.g(), a.b()]);
+		let b = lab_from_argb([0xff, b.r(), b.g(), b.b()]);
+		((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+	}
+
+	#[test]
+	fn grey_stays_grey() {
+		for vision in [ColorVision::Protan, ColorVision::Deutan, ColorVision::Tritan] {
+			let grey = simulate(Color32::from_gray(128), vision);
+			assert!(distance(grey, Color32::from_gray(128)) < 2.0, "{vision:?} {grey:?}");
+		}
+	}
+
+	#[test]
+	fn decline_and_accept_stay_apart() {
+		for vision in [ColorVision::Protan, ColorVision::Deutan, ColorVision::Tritan] {
+			for dark_mode in [true, false] {
+				let theme = Theme::with_vision([0xe5, 0x4c, 0x64], dark_mode, Contrast::Standard, vision);
+				let red = simulate(theme.red.color, vision);
+				let green = simulate(theme.green.color, vision);
+				assert!(distance(red, green) > 25.0, "{vision:?} {dark_mode} {}", distance(red, green));
+			}
+		}
+	}
+}
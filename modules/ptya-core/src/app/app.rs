@@ -1,3 +1,4 @@
+use crate::color::Theme;
 use crate::ui::Pui;
 use egui::Id;
 use glium::framebuffer::SimpleFrameBuffer;
@@ -10,6 +11,9 @@ pub trait App {
 
 	/// Runs when the system settings get applied.
 	fn update(&mut self, system: &System);
+
+	/// Runs when the theme changes, [Pui] already gives the right colors so this is only needed for cached colors.
+	fn theme_changed(&mut self, _theme: &Theme) {}
 }
 
 #[derive(Clone)]
@@ -18,6 +22,9 @@ pub struct Manifest {
 	pub name: String,
 	pub icon: u32,
 	pub version: Version,
+	/// Named colors the app uses, these get harmonized to the theme and are drawn with [ColorTag::Custom](crate::color::ColorTag::Custom).
+	/// Names are shared by all apps, so pick names that are unlikely to clash like `"maps_eco"`.
+	pub colors: Vec<(&'static str, [u8; 3])>,
 	//pub icon: Icon,
 }
 
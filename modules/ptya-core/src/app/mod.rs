@@ -2,6 +2,7 @@ pub use crate::app::app::App;
 pub use crate::app::app::AppId;
 pub use crate::app::app::Manifest;
 pub use crate::app::container::AppContainer;
+use crate::color::Theme;
 use crate::System;
 use ahash::AHashMap;
 use egui::mutex::{Mutex, MutexGuard};
@@ -32,6 +33,15 @@ impl AppManager {
 			container.app().update(system);
 		}
 	}
+
+	/// Tells every app the theme changed.
+	pub fn theme_changed(&self, theme: &Theme) {
+		let mut apps = self.apps.lock();
+		for container in apps.values_mut() {
+			container.app().theme_changed(theme);
+		}
+	}
+
 	pub fn load_app(
 		&self,
 		ctx: &Rc<Context>,
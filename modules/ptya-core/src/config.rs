@@ -7,4 +7,41 @@ use crate::color::config::ColorConfig;
 pub struct Config {
 	pub color: ColorConfig,
 	pub animation: AnimationConfig,
+	#[serde(default)]
+	pub gesture: GestureConfig,
+}
+
+/// Thresholds for recognizing gestures, drivers with gloves or a bumpy road may need more room.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct GestureConfig {
+	/// How far in points a finger can move before a tap becomes a drag.
+	pub tap_slop: f32,
+	/// The most time in seconds between two taps of a double tap.
+	pub double_tap_time: f64,
+	/// How long in seconds a finger has to rest for a long press.
+	pub long_press_time: f64,
+	/// The speed in points per second a drag has to be released with to be a swipe.
+	pub swipe_velocity: f32,
+	/// How far from the edge of the screen in points an edge swipe can start.
+	pub edge_size: f32,
+}
+
+impl Default for GestureConfig {
+	fn default() -> Self {
+		GestureConfig {
+			tap_slop: 15.0,
+			double_tap_time: 0.3,
+			long_press_time: 0.5,
+			swipe_velocity: 600.0,
+			edge_size: 30.0,
+		}
+	}
+}
+
+/// The apps open in the content area, saved per profile.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct LayoutConfig {
+	pub primary: Option<String>,
+	pub widgets: Vec<String>,
 }
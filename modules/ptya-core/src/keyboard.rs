@@ -0,0 +1,395 @@
+use egui::mutex::Mutex;
+use egui::{Id, Pos2, Rect};
+
+/// How many suggestions the keyboard shows above the keys.
+pub const SUGGESTIONS: usize = 3;
+
+/// Common words for suggestions before the driver has typed anything.
+const WORDS: &[&str] = &[
+	"the", "and", "to", "of", "in", "is", "it", "you", "that", "for", "on", "with", "home", "work", "street",
+	"road", "avenue", "station", "airport", "parking", "hospital", "restaurant", "hotel", "school", "shop",
+	"supermarket", "gas", "charging", "coffee", "center", "north", "south", "east", "west", "music", "navigate",
+	"call", "message", "hello", "thanks", "yes", "no", "please", "later", "minutes", "arriving", "on my way",
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum KeyboardLayout {
+	#[default]
+	Qwerty,
+	Numeric,
+	Symbols,
+}
+
+/// A key on the on-screen keyboard.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Key {
+	Char(char),
+	Backspace,
+	Space,
+	Enter,
+	Shift,
+	Layout(KeyboardLayout),
+	/// Closes the keyboard without submitting.
+	Hide,
+}
+
+/// A key and how wide it is compared to a letter key.
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyCap {
+	pub key: Key,
+	pub width: f32,
+}
+
+impl KeyCap {
+	fn new(key: Key) -> KeyCap {
+		KeyCap { key, width: 1.0 }
+	}
+
+	fn wide(key: Key, width: f32) -> KeyCap {
+		KeyCap { key, width }
+	}
+}
+
+impl KeyboardLayout {
+	/// The rows of keys from top to bottom.
+	pub fn rows(self, shift: bool) -> Vec<Vec<KeyCap>> {
+		let chars = |row: &str| -> Vec<KeyCap> {
+			row.chars()
+				.map(|c| KeyCap::new(Key::Char(if shift { c.to_ascii_uppercase() } else { c })))
+				.collect()
+		};
+
+		let mut rows = match self {
+			KeyboardLayout::Qwerty => {
+				let mut third = vec![KeyCap::wide(Key::Shift, 1.5)];
+				third.extend(chars("zxcvbnm"));
+				third.push(KeyCap::wide(Key::Backspace, 1.5));
+				vec![chars("qwertyuiop"), chars("asdfghjkl"), third]
+			}
+			KeyboardLayout::Numeric => {
+				let mut last = chars(".0");
+				last.push(KeyCap::new(Key::Backspace));
+				vec![chars("123"), chars("456"), chars("789"), last]
+			}
+			KeyboardLayout::Symbols => {
+				let mut third = chars("-_'\"/\\");
+				third.push(KeyCap::wide(Key::Backspace, 1.5));
+				vec![chars("1234567890"), chars("@#$%&*()+="), third]
+			}
+		};
+
+		let switch = match self {
+			KeyboardLayout::Qwerty => KeyCap::wide(Key::Layout(KeyboardLayout::Symbols), 1.5),
+			_ => KeyCap::wide(Key::Layout(KeyboardLayout::Qwerty), 1.5),
+		};
+		rows.push(vec![
+			switch,
+			KeyCap::new(Key::Char(',')),
+			KeyCap::wide(Key::Space, 5.0),
+			KeyCap::new(Key::Char('.')),
+			KeyCap::new(Key::Hide),
+			KeyCap::wide(Key::Enter, 1.5),
+		]);
+		rows
+	}
+}
+
+/// Input from the keyboard for the text field it is open for.
+#[derive(Clone, PartialEq, Debug)]
+pub enum KeyInput {
+	Char(char),
+	Backspace,
+	/// Replaces the word in front of the cursor, from a suggestion.
+	Word(String),
+	Submit,
+}
+
+/// The system on-screen keyboard, text fields open it and the frontend draws it.
+/// Input is queued here and picked up by the text field on the next frame.
+/// It closes when its text field is not shown anymore or the screen is touched somewhere else.
+#[derive(Default)]
+pub struct Keyboard {
+	inner: Mutex<KeyboardState>,
+}
+
+#[derive(Default)]
+struct KeyboardState {
+	target: Option<Id>,
+	layout: KeyboardLayout,
+	shift: bool,
+	// A copy of the text in the field, used for suggestions.
+	text: String,
+	// Passwords are not suggested and not learned.
+	private: bool,
+	input: Vec<KeyInput>,
+	// Where the text field and the panel were drawn this frame.
+	field: Option<Rect>,
+	panel: Option<Rect>,
+	// Words the driver has submitted with how often, these go before the built in words.
+	learned: Vec<(String, u32)>,
+}
+
+impl Keyboard {
+	/// Opens the keyboard for a text field.
+	pub fn open(&self, target: Id, layout: KeyboardLayout, private: bool) {
+		let mut inner = self.inner.lock();
+		if inner.target != Some(target) {
+			inner.target = Some(target);
+			inner.layout = layout;
+			inner.shift = false;
+			inner.private = private;
+			inner.input.clear();
+		}
+	}
+
+	pub fn close(&self) {
+		let mut inner = self.inner.lock();
+		inner.target = None;
+		inner.input.clear();
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.inner.lock().target.is_some()
+	}
+
+	/// Checks if the keyboard is open for a text field.
+	pub fn is_target(&self, id: Id) -> bool {
+		self.inner.lock().target == Some(id)
+	}
+
+	pub fn layout(&self) -> KeyboardLayout {
+		self.inner.lock().layout
+	}
+
+	pub fn shift(&self) -> bool {
+		self.inner.lock().shift
+	}
+
+	/// Handles a key press from the keyboard panel.
+	pub fn press(&self, key: &Key) {
+		let mut inner = self.inner.lock();
+		match key {
+			Key::Char(c) => {
+				inner.input.push(KeyInput::Char(*c));
+				// Shift only applies to one letter.
+				inner.shift = false;
+			}
+			Key::Backspace => inner.input.push(KeyInput::Backspace),
+			Key::Space => inner.input.push(KeyInput::Char(' ')),
+			Key::Enter => inner.input.push(KeyInput::Submit),
+			Key::Shift => inner.shift = !inner.shift,
+			Key::Layout(layout) => inner.layout = *layout,
+			Key::Hide => inner.target = None,
+		}
+	}
+
+	/// Uses a suggestion for the word that is being typed.
+	pub fn suggest(&self, word: &str) {
+		self.inner.lock().input.push(KeyInput::Word(format!("{word} ")));
+	}
+
+	/// Takes the input for a text field, the field needs to call this every frame or the keyboard closes.
+	pub fn take_input(&self, target: Id, rect: Rect) -> Vec<KeyInput> {
+		let mut inner = self.inner.lock();
+		if inner.target != Some(target) {
+			return Vec::new();
+		}
+		inner.field = Some(rect);
+		std::mem::take(&mut inner.input)
+	}
+
+	/// Tells the keyboard the text after the input was applied, used for suggestions.
+	pub fn set_text(&self, target: Id, text: &str) {
+		let mut inner = self.inner.lock();
+		if inner.target == Some(target) {
+			inner.text.clear();
+			inner.text.push_str(text);
+		}
+	}
+
+	/// Closes the keyboard for submitted text, its words are suggested first from now on.
+	pub fn submit(&self, text: &str) {
+		let mut inner = self.inner.lock();
+		if !inner.private {
+			learn(&mut inner.learned, text);
+		}
+		inner.target = None;
+		inner.input.clear();
+	}
+
+	/// Tells the keyboard where its panel is drawn, touching it does not close the keyboard.
+	pub fn set_panel(&self, rect: Rect) {
+		self.inner.lock().panel = Some(rect);
+	}
+
+	/// Closes the keyboard if its text field went away or the screen was pressed outside of it and the panel.
+	/// Call it after the ui is drawn.
+	pub fn end_frame(&self, pressed_at: Option<Pos2>) {
+		let mut inner = self.inner.lock();
+		let (field, panel) = (inner.field.take(), inner.panel.take());
+		if inner.target.is_none() {
+			return;
+		}
+
+		let inside = |rect: Option<Rect>, pos: Pos2| rect.is_some_and(|rect| rect.contains(pos));
+		let outside = pressed_at.is_some_and(|pos| !inside(field, pos) && !inside(panel, pos));
+		if field.is_none() || outside {
+			inner.target = None;
+			inner.input.clear();
+		}
+	}
+
+	/// Suggestions for the word that is being typed.
+	pub fn suggestions(&self) -> Vec<String> {
+		let inner = self.inner.lock();
+		if inner.private {
+			return Vec::new();
+		}
+		suggest(current_word(&inner.text), &inner.learned)
+	}
+}
+
+/// The word in front of the cursor, the cursor is always at the end.
+pub fn current_word(text: &str) -> &str {
+	text.rsplit(char::is_whitespace).next().unwrap_or("")
+}
+
+/// Suggests words that start with the prefix, learned words first by how often they were used.
+fn suggest(prefix: &str, learned: &[(String, u32)]) -> Vec<String> {
+	if prefix.is_empty() {
+		return Vec::new();
+	}
+	let prefix = prefix.to_lowercase();
+
+	let mut learned: Vec<_> = learned.iter().filter(|(word, _)| word.starts_with(&prefix)).collect();
+	learned.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+	let mut suggestions: Vec<String> = Vec::new();
+	let words = learned
+		.into_iter()
+		.map(|(word, _)| word.as_str())
+		.chain(WORDS.iter().copied().filter(|word| word.starts_with(&prefix)));
+	for word in words {
+		if word != prefix && !suggestions.iter().any(|existing| existing == word) {
+			suggestions.push(word.to_string());
+		}
+		if suggestions.len() == SUGGESTIONS {
+			break;
+		}
+	}
+	suggestions
+}
+
+fn learn(learned: &mut Vec<(String, u32)>, text: &str) {
+	for word in text.split_whitespace() {
+		let word = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+		if word.len() < 3 {
+			continue;
+		}
+		match learned.iter_mut().find(|(existing, _)| *existing == word) {
+			Some((_, count)) => *count += 1,
+			None => learned.push((word, 1)),
+		}
+	}
+}
+
+/// Applies keyboard input to a string, returns true if the text was submitted.
+pub fn apply_input(text: &mut String, input: Vec<KeyInput>) -> bool {
+	let mut submitted = false;
+	for input in input {
+		match input {
+			KeyInput::Char(c) => text.push(c),
+			KeyInput::Backspace => {
+				text.pop();
+			}
+			KeyInput::Word(word) => {
+				let keep = text.len() - current_word(text).len();
+				text.truncate(keep);
+				text.push_str(&word);
+			}
+			KeyInput::Submit => submitted = true,
+		}
+	}
+	submitted
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn suggestions_prefer_learned_words() {
+		assert_eq!(suggest("sta", &[]), vec!["station"]);
+		assert!(suggest("", &[]).is_empty());
+
+		let mut learned = Vec::new();
+		learn(&mut learned, "Stockholm stadium, stadium");
+		assert_eq!(suggest("St", &learned), vec!["stadium", "stockholm", "street"]);
+	}
+
+	#[test]
+	fn input_edits_the_last_word() {
+		let mut text = "drive to sta".to_string();
+		let submitted = apply_input(
+			&mut text,
+			vec![KeyInput::Word("station ".into()), KeyInput::Char('x'), KeyInput::Backspace],
+		);
+		assert_eq!(text, "drive to station ");
+		assert!(!submitted);
+		assert!(apply_input(&mut text, vec![KeyInput::Submit]));
+	}
+
+	#[test]
+	fn the_keyboard_closes_with_its_field() {
+		let keyboard = Keyboard::default();
+		let field = Id::new("field");
+		let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(200.0, 50.0));
+		let panel = Rect::from_min_size(Pos2::new(0.0, 500.0), egui::vec2(800.0, 300.0));
+		let frame = |pressed_at: Option<Pos2>| {
+			keyboard.take_input(field, rect);
+			keyboard.set_panel(panel);
+			keyboard.end_frame(pressed_at);
+			keyboard.is_target(field)
+		};
+
+		keyboard.open(field, KeyboardLayout::Qwerty, false);
+		assert!(frame(None));
+		assert!(frame(Some(Pos2::new(10.0, 10.0))));
+		assert!(frame(Some(Pos2::new(400.0, 600.0))));
+		assert!(!frame(Some(Pos2::new(400.0, 100.0))));
+
+		// A field that is not shown anymore does not keep it open.
+		keyboard.open(field, KeyboardLayout::Qwerty, false);
+		assert!(frame(None));
+		keyboard.end_frame(None);
+		assert!(!keyboard.is_open());
+	}
+
+	#[test]
+	fn submitted_text_is_learned() {
+		let keyboard = Keyboard::default();
+		let field = Id::new("field");
+		keyboard.open(field, KeyboardLayout::Qwerty, false);
+		keyboard.submit("Stadium");
+		assert!(!keyboard.is_open());
+
+		keyboard.open(field, KeyboardLayout::Qwerty, false);
+		keyboard.set_text(field, "sta");
+		assert_eq!(keyboard.suggestions()[0], "stadium");
+
+		keyboard.open(Id::new("password"), KeyboardLayout::Qwerty, true);
+		keyboard.submit("secret");
+		keyboard.open(field, KeyboardLayout::Qwerty, false);
+		keyboard.set_text(field, "sec");
+		assert!(keyboard.suggestions().is_empty());
+	}
+
+	#[test]
+	fn every_layout_can_type_and_delete() {
+		for layout in [KeyboardLayout::Qwerty, KeyboardLayout::Numeric, KeyboardLayout::Symbols] {
+			let keys: Vec<_> = layout.rows(false).into_iter().flatten().map(|cap| cap.key).collect();
+			assert!(keys.contains(&Key::Backspace), "{layout:?}");
+			assert!(keys.contains(&Key::Enter), "{layout:?}");
+		}
+	}
+}
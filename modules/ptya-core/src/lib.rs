@@ -9,18 +9,22 @@ pub mod color {
 	pub use ptya_color::*;
 }
 pub mod config;
+pub mod keyboard;
 pub mod task;
+mod theme;
 pub mod ui;
 
-use crate::config::Config;
-use crate::task::Task;
+use crate::config::{Config, LayoutConfig};
+use crate::keyboard::Keyboard;
+use crate::task::{Task, TaskAlreadyInProgress};
 use anyways::ext::AuditExt;
 use anyways::Result;
 use egui::{CentralPanel, Color32, Frame, Spinner, Widget};
 use glium::backend::Context;
-use log::{info, LevelFilter};
+use log::{error, info, LevelFilter};
 use ptya_animation::AnimationManager;
-use ptya_asset::{AssetManager, Location};
+use ptya_asset::{AssetManager, Location, Profiles};
+use ptya_color::config::ThemeTag;
 use ptya_color::ColorManager;
 use simplelog::{ColorChoice, CombinedLogger, TermLogger, TerminalMode, WriteLogger};
 use std::fs::File;
@@ -30,7 +34,10 @@ use std::sync::Arc;
 use tokio::join;
 use tokio::runtime::Runtime;
 
-use crate::app::AppManager;
+use crate::app::{App, AppManager, Manifest};
+use crate::theme::load_theme_files;
+use crate::ui::focus::{self, Focus, FocusInput};
+use crate::ui::gesture::Gestures;
 use crate::ui::UiAssets;
 
 pub struct System {
@@ -39,6 +46,8 @@ pub struct System {
 	pub runtime: Arc<Runtime>,
 
 	pub app: AppManager,
+	pub keyboard: Keyboard,
+	pub focus: Focus,
 
 	task: Task<Result<InitializedSystem>>,
 	inner: Option<InitializedSystem>,
@@ -52,7 +61,7 @@ impl System {
 		let mut task = Task::new(&runtime);
 		task.launch(async {
 			info!("Launching inner system");
-			InitializedSystem::new().await
+			InitializedSystem::new(None).await
 		})
 		.unwrap();
 
@@ -61,6 +70,8 @@ impl System {
 			egui_ctx: ctx,
 			runtime,
 			app: AppManager::new(),
+			keyboard: Keyboard::default(),
+			focus: Focus::default(),
 			task,
 			inner: None,
 		})
@@ -80,24 +91,104 @@ impl System {
 						Spinner::new().size(40.0).ui(ui);
 					});
 				});
+		} else if let Some(inner) = &mut self.inner {
+			inner.animation.tick(&self.egui_ctx);
+			inner.gesture.end_frame(&self.egui_ctx);
 
-			if let Some(value) = self.task.try_recv() {
-				let mut system = value.wrap_err("Failed to initialize pitaya")?;
-				if let Some(assets) = system.assets.take() {
-					assets.apply(self.egui_ctx.clone());
+			let inputs = focus::inputs(&self.egui_ctx);
+			let pointer_pressed = self.egui_ctx.input().pointer.any_pressed();
+			let pressed_at = self.egui_ctx.input().pointer.interact_pos().filter(|_| pointer_pressed);
+			self.keyboard.end_frame(pressed_at);
+			if self.keyboard.is_open() {
+				// Keys go to the text field while the keyboard is open, back closes it.
+				if inputs.contains(&FocusInput::Back) {
+					self.keyboard.close();
 				}
+				self.focus.end_frame(&[], pointer_pressed);
+			} else {
+				self.focus.end_frame(&inputs, pointer_pressed);
+			}
+			if inner.color.tick(&inner.animation) {
+				self.app.theme_changed(inner.color.target_theme());
+			}
+		}
 
-				self.app.apps().clear();
-				self.inner = Some(system);
-				info!("Initialized system");
-				updated = true;
+		if let Some(value) = self.task.try_recv() {
+			let mut system = match value {
+				Ok(system) => system,
+				// A failed profile switch keeps the current profile running.
+				Err(err) if self.inner.is_some() => {
+					error!("Failed to switch profile {err}");
+					return Ok(false);
+				}
+				Err(err) => return Err(err).wrap_err("Failed to initialize pitaya"),
+			};
+			if let Some(assets) = system.assets.take() {
+				assets.apply(self.egui_ctx.clone());
 			}
-		} else {
-			self.animation.tick(&self.egui_ctx);
+
+			self.app.apps().clear();
+			info!("Initialized system for profile {:?}", system.asset.profile());
+			self.inner = Some(system);
+			updated = true;
 		}
 
 		Ok(updated)
 	}
+
+	/// Loads an app and registers its colors.
+	pub fn load_app(&mut self, manifest: Manifest, app: Box<dyn App>) {
+		for (name, rgb) in &manifest.colors {
+			self.color.register_color(name, *rgb);
+		}
+		self.app.load_app(&self.gl_ctx, manifest, app);
+	}
+
+	/// Reloads the system with another profile, the current profile stays active until it has loaded.
+	pub fn switch_profile(&mut self, id: String) -> Result<(), TaskAlreadyInProgress> {
+		info!("Switching to profile {id:?}");
+		self.task.launch(async move { InitializedSystem::new(Some(id)).await })
+	}
+
+	/// Creates a new profile and switches to it.
+	pub fn create_profile(&mut self, name: String) -> Result<(), TaskAlreadyInProgress> {
+		self.task.launch(async move {
+			let asset = AssetManager::new()
+				.await
+				.wrap_err("Failed to init asset manager")?;
+			let profile = asset
+				.create_profile(name)
+				.await
+				.wrap_err("Failed to create profile")?;
+			InitializedSystem::new(Some(profile.id)).await
+		})
+	}
+
+	/// Sets if the profile picker is shown on every boot, this is saved in the background.
+	pub fn set_pick_on_boot(&mut self, pick_on_boot: bool) {
+		self.profiles.pick_on_boot = pick_on_boot;
+		let asset = self.asset.clone();
+		self.runtime.spawn(async move {
+			if let Err(err) = asset.set_pick_on_boot(pick_on_boot).await {
+				error!("Failed to save profiles {err}");
+			}
+		});
+	}
+
+	/// Checks if a profile is currently being loaded.
+	pub fn is_switching(&self) -> bool {
+		self.inner.is_some() && self.task.in_progress()
+	}
+
+	/// Saves the content layout to the current profile in the background.
+	pub fn save_layout(&self, layout: LayoutConfig) {
+		let asset = self.asset.clone();
+		self.runtime.spawn(async move {
+			if let Err(err) = asset.save_data(Location::Config, "layout.json", &layout).await {
+				error!("Failed to save layout {err}");
+			}
+		});
+	}
 }
 
 impl Deref for System {
@@ -122,29 +213,59 @@ pub struct InitializedSystem {
 	pub asset: AssetManager,
 	pub color: ColorManager,
 	pub animation: AnimationManager,
+	pub profiles: Profiles,
+	pub layout: LayoutConfig,
+	pub gesture: Gestures,
 	assets: Option<UiAssets>,
 }
 
 impl InitializedSystem {
-	pub async fn new() -> Result<InitializedSystem> {
-		let asset: AssetManager = AssetManager::new()
+	/// Initializes the system for a profile, [None] uses the last active profile.
+	pub async fn new(profile: Option<String>) -> Result<InitializedSystem> {
+		let mut asset: AssetManager = AssetManager::new()
 			.await
 			.wrap_err("Failed to init asset manager")?;
+		if let Some(profile) = profile {
+			asset = asset
+				.select_profile(&profile)
+				.await
+				.wrap_err("Failed to select profile")?;
+		}
+		let profiles = asset.profiles().await.wrap_err("Failed to read profiles")?;
 
 		let config: Config = asset
 			.get_data(Location::Config, "config.json")
 			.await
 			.wrap_err("Failed to read config")?;
+		let layout: LayoutConfig = asset
+			.get_data(Location::Config, "layout.json")
+			.await
+			.wrap_err("Failed to read layout")?;
+
+		let themes = load_theme_files(&asset).await;
+		// Fonts are only loaded here, so the typography of the theme at startup is kept for the session.
+		let typography = match &config.color.theme {
+			ThemeTag::File(name) => themes
+				.iter()
+				.find(|file| &file.name == name)
+				.map(|file| file.typography.clone()),
+			_ => None,
+		}
+		.unwrap_or_default();
 
-		let color = ColorManager::new(config.color);
+		let gesture = Gestures::new(config.gesture);
+		let color = ColorManager::new(config.color, themes);
 		let animation = AnimationManager::new(config.animation);
-		let ui = UiAssets::new(&asset);
+		let ui = UiAssets::new(&asset, &typography);
 		let (color, animation, ui) = join!(color, animation, ui);
 		let ui = ui.wrap_err("Failed to init ui")?;
 		Ok(InitializedSystem {
 			asset,
 			color,
 			animation,
+			profiles,
+			layout,
+			gesture,
 			assets: Some(ui),
 		})
 	}
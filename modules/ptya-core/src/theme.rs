@@ -0,0 +1,45 @@
+use crate::asset::{AssetManager, Location};
+use crate::color::ThemeFile;
+use anyways::ext::AuditExt;
+use anyways::Result;
+use log::{error, info};
+use std::path::Path;
+
+/// Loads the theme files in `themes/` of the assets and then the config location.
+/// A config theme with the same name as an asset theme replaces it. Broken files are logged and skipped.
+pub async fn load_theme_files(asset: &AssetManager) -> Vec<ThemeFile> {
+	let mut files: Vec<ThemeFile> = Vec::new();
+	for location in [Location::Assets, Location::Config] {
+		// Most installations do not have custom themes.
+		let paths = match asset.read_dir(location, "themes").await {
+			Ok(paths) => paths,
+			Err(_) => continue,
+		};
+
+		for path in paths {
+			if path.extension().is_none_or(|extension| extension != "json") {
+				continue;
+			}
+
+			match load_theme_file(asset, location, &path).await {
+				Ok(file) => {
+					info!("Loaded theme {:?} from {path:?}", file.name);
+					files.retain(|existing| existing.name != file.name);
+					files.push(file);
+				}
+				Err(err) => error!("Failed to load theme {path:?} {err:?}"),
+			}
+		}
+	}
+	files
+}
+
+async fn load_theme_file(asset: &AssetManager, location: Location, path: &Path) -> Result<ThemeFile> {
+	let data = asset
+		.read_file(location, path)
+		.await
+		.wrap_err("Failed to read theme file")?;
+	let file: ThemeFile = serde_json::from_slice(&data).wrap_err("Failed to parse theme file")?;
+	file.validate().wrap_err("Invalid theme file")?;
+	Ok(file)
+}
@@ -1,7 +1,8 @@
 use egui::{Align2, FontFamily, FontId, Response, RichText, Stroke, WidgetText};
-use crate::animation::{Easing, Lerp};
+use crate::animation::{AnimationRef, Easing, Lerp};
 use crate::color::ColorTag;
 use crate::ui::components::Text;
+use crate::ui::focus::Focusable;
 use crate::ui::Pui;
 use crate::ui::util::alloc_intractable;
 
@@ -29,8 +30,9 @@ impl Button {
         );
 
         let (rect, response, rounding) = alloc_intractable(ui, text.width());
+        let response = response.focusable(ui);
 
-        let mut click_animation = ui.sys().animation.get::<f32>(response.id);
+        let mut click_animation = ui.sys().animation.get_ref(AnimationRef::<f32>::new(response.id));
         if response.clicked() || (response.drag_released() && response.hovered()) {
             click_animation
                 .set_easing(Easing::EaseOut)
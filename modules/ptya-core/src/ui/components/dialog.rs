@@ -0,0 +1,139 @@
+use crate::animation::AnimationRef;
+use crate::color::ColorTag;
+use crate::ui::components::{Button, Text};
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
+use egui::{Align, Align2, FontFamily, FontId, Id, Layout, Pos2, Rect, Sense, Vec2};
+use std::hash::Hash;
+
+const WIDTH: f32 = 800.0;
+const PADDING: f32 = SPACING_SIZE * 2.0;
+
+/// A modal dialog that asks the driver to confirm something, like deleting a saved route.
+/// The content behind it is covered by a scrim and can not be used while it is open.
+pub struct Dialog {
+	id: Id,
+	pub title: String,
+	pub text: String,
+	pub confirm: String,
+	/// The dialog can only be closed without confirming if there is a dismiss button.
+	pub dismiss: Option<String>,
+	/// The color of the confirm button, use red for things that can not be undone.
+	pub color: ColorTag,
+}
+
+impl Dialog {
+	pub fn new(
+		id_source: impl Hash,
+		title: impl Into<String>,
+		text: impl Into<String>,
+		confirm: impl Into<String>,
+	) -> Dialog {
+		Dialog {
+			id: Id::new(id_source),
+			title: title.into(),
+			text: text.into(),
+			confirm: confirm.into(),
+			dismiss: Some("Cancel".to_string()),
+			color: ColorTag::Primary,
+		}
+	}
+
+	/// Shows the dialog while open is true, it is set to false when the dialog is closed.
+	pub fn show(self, ui: &mut Pui, open: &mut bool) -> DialogResponse {
+		let mut response = DialogResponse::default();
+
+		let mut animation = ui.sys().animation.get_ref(AnimationRef::<f32>::new(self.id));
+		if ui.sys().animation.reduced_motion() {
+			animation.set_value(*open as u8 as f32);
+		} else {
+			animation.redirect(*open as u8 as f32);
+		}
+		let shown = animation.get_value();
+		drop(animation);
+		if shown == 0.0 {
+			return response;
+		}
+
+		let screen = ui.ctx().input().screen_rect();
+		let color = ui.color().dialog();
+		let width = WIDTH.min(screen.width() - SPACING_SIZE * 2.0);
+		let title = Text::new(
+			ui,
+			&self.title,
+			Some(width - PADDING * 2.0),
+			FontId::new(50.0, FontFamily::Name("Roboto-Medium".into())),
+		);
+		let text = Text::new(
+			ui,
+			&self.text,
+			Some(width - PADDING * 2.0),
+			FontId::new(35.0, FontFamily::Name("Roboto-Regular".into())),
+		);
+		let height = PADDING + title.height() + SPACING_SIZE + text.height() + PADDING + INTERACTIVE_SIZE + PADDING;
+		let card = Rect::from_center_size(screen.center(), Vec2::new(width, height));
+		// The card unrolls from its top, everything in it is clipped to the part that is shown.
+		let clip = Rect::from_min_size(card.min, Vec2::new(width, height * shown));
+
+		ui.overlay(self.id.with("overlay"), screen, |ui| {
+			ui.color = color;
+			ui.sys().focus.trap(card);
+			let scrim = ui.interact(screen, self.id.with("scrim"), Sense::click());
+			let outside = scrim.interact_pointer_pos().is_some_and(|pos| !card.contains(pos));
+			if scrim.clicked() && outside && self.dismiss.is_some() {
+				response.dismissed = true;
+			}
+
+			let painter = ui.painter();
+			painter.rect_filled(screen, 0.0, color.scrim.linear_multiply(shown));
+			let painter = ui.painter_at(clip);
+			painter.rect_filled(card, color.rounding(), color.bg());
+			let top = card.min + Vec2::new(PADDING, PADDING);
+			title.draw(&painter, top, Align2::LEFT_TOP, color.fg);
+			text.draw(
+				&painter,
+				top + Vec2::new(0.0, title.height() + SPACING_SIZE),
+				Align2::LEFT_TOP,
+				color.on_surface_variant,
+			);
+
+			let buttons = Rect::from_min_max(
+				Pos2::new(card.min.x + PADDING, card.max.y - PADDING - INTERACTIVE_SIZE),
+				card.max - Vec2::new(PADDING, PADDING),
+			);
+			let mut row = ui.child_ui(buttons, Layout::right_to_left(Align::Center));
+			row.set_clip_rect(clip);
+			let mut row = ui.ui(&mut row);
+			if Button::new(&self.confirm, self.color).ui(&mut row).clicked() {
+				response.confirmed = true;
+			}
+			if let Some(dismiss) = &self.dismiss {
+				if Button::new(dismiss, ColorTag::Tertiary).ui(&mut row).clicked() {
+					response.dismissed = true;
+				}
+			}
+		});
+
+		if response.confirmed || response.dismissed {
+			*open = false;
+		}
+		response
+	}
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct DialogResponse {
+	confirmed: bool,
+	dismissed: bool,
+}
+
+impl DialogResponse {
+	/// Checks if the confirm button was pressed, the dialog closes when it is.
+	pub fn confirmed(&self) -> bool {
+		self.confirmed
+	}
+
+	/// Checks if the dialog was closed without confirming.
+	pub fn dismissed(&self) -> bool {
+		self.dismissed
+	}
+}
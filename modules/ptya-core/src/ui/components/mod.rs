@@ -2,13 +2,44 @@
 //! - Button
 //! - Slider
 //! - Progress Indicator
+//! - Text Field
+//! - Range Slider
+//! - Toggle
+//! - Scroll View and Virtual List
+//! - Dialog, Bottom Sheet and Snackbar
+//! - Tab Bar, Segmented Button and Pages
 mod button;
+mod dialog;
 mod slider;
+mod snackbar;
+mod pages;
 mod progress;
+mod range_slider;
+mod scroll;
+mod segmented;
+mod sheet;
+mod tabs;
 mod text;
+mod text_field;
+mod toggle;
 
 pub use button::Button;
+pub use dialog::Dialog;
+pub use dialog::DialogResponse;
 pub use slider::Slider;
 pub use slider::SliderResponse;
+pub use snackbar::Snackbar;
+pub use snackbar::SnackbarResponse;
+pub use pages::Pages;
 pub use progress::ProgressSpinner;
-pub use text::Text;
\ No newline at end of file
+pub use range_slider::RangeSlider;
+pub use scroll::ScrollView;
+pub use scroll::VirtualList;
+pub use segmented::SegmentedButton;
+pub use sheet::BottomSheet;
+pub use tabs::Tab;
+pub use tabs::TabBar;
+pub use text::Text;
+pub use text_field::TextField;
+pub use text_field::TextFieldResponse;
+pub use toggle::Toggle;
\ No newline at end of file
@@ -0,0 +1,154 @@
+use crate::animation::{AnimationImpl, AnimationRef, Spring};
+use crate::ui::gesture::GestureSense;
+use crate::ui::Pui;
+use egui::{Id, Response, Sense, Vec2};
+use std::hash::Hash;
+
+/// Swiping faster than this in pages per second goes to the next page even if it was moved less than half way.
+const FLING_VELOCITY: f32 = 0.5;
+/// How much of the finger movement is followed past the first and last page.
+const EDGE_RESISTANCE: f32 = 0.3;
+
+#[derive(Copy, Clone, Default)]
+struct PagesState {
+	dragging: bool,
+	// The page position the finger is at, without the resistance past the first and last page.
+	raw: f32,
+}
+
+/// Pages next to each other that can be swiped between, pair it with a [TabBar](crate::ui::components::TabBar)
+/// using the same selected page so tapping a tab slides to its page.
+/// Only sideways drags swipe, so pages can hold a [ScrollView](crate::ui::components::ScrollView).
+pub struct Pages<'a> {
+	id: Id,
+	pub selected: &'a mut usize,
+	pub count: usize,
+	/// Defaults to the available height.
+	pub height: Option<f32>,
+}
+
+impl<'a> Pages<'a> {
+	pub fn new(id_source: impl Hash, selected: &'a mut usize, count: usize) -> Pages<'a> {
+		Pages {
+			id: Id::new(id_source),
+			selected,
+			count,
+			height: None,
+		}
+	}
+
+	/// Shows the pages, the page is added for every page that is visible.
+	/// Two pages are visible while swiping.
+	pub fn show(self, ui: &mut Pui, mut add_page: impl FnMut(&mut Pui, usize)) -> Response {
+		let id = ui.make_persistent_id(self.id);
+		let size = Vec2::new(
+			ui.available_width(),
+			self.height.unwrap_or_else(|| ui.available_height()),
+		);
+		let (viewport, mut response) = ui.allocate_exact_size(size, Sense::hover());
+		if self.count == 0 {
+			return response;
+		}
+		*self.selected = (*self.selected).min(self.count - 1);
+
+		let last = (self.count - 1) as f32;
+		let reduced_motion = ui.sys().animation.reduced_motion();
+		let selected = *self.selected as f32;
+		let mut position = ui.sys().animation.get_ref_or(AnimationRef::new(id), || AnimationImpl::spring(selected, Spring::SNAPPY));
+		let mut state: PagesState = ui.data().get_temp(id).unwrap_or_default();
+		let gestures = ui.gestures(id, viewport, GestureSense::drag_x());
+		let pointer = ui.input().pointer.clone();
+
+		let delta = gestures.drag_delta();
+		if delta != Vec2::ZERO {
+			if !state.dragging {
+				state.dragging = true;
+				state.raw = position.get_value();
+			}
+			state.raw -= delta.x / viewport.width();
+			let mut value = state.raw;
+			if value < 0.0 {
+				value *= EDGE_RESISTANCE;
+			} else if value > last {
+				value = last + (value - last) * EDGE_RESISTANCE;
+			}
+			position.set_value(value);
+		}
+
+		if state.dragging {
+			// The finger was lifted.
+			if !pointer.any_down() {
+				state.dragging = false;
+				let current = position.get_value();
+				let velocity = -pointer.velocity().x / viewport.width();
+				let page = settle_page(current, velocity, self.count);
+				if page != *self.selected {
+					*self.selected = page;
+					response.mark_changed();
+				}
+				let target = page as f32;
+				if reduced_motion || target == current {
+					position.set_value(target);
+				} else {
+					// The page keeps the speed of the finger.
+					position
+						.set_spring(Spring::SNAPPY)
+						.retarget(target)
+						.set_velocity((velocity / (target - current)) as f64);
+				}
+			}
+		} else if *position.get_to() != *self.selected as f32 {
+			// A tab was selected.
+			if reduced_motion {
+				position.set_value(*self.selected as f32);
+			} else {
+				position.set_spring(Spring::SNAPPY).retarget(*self.selected as f32);
+			}
+		}
+		ui.data().insert_temp(id, state);
+		let value = position.get_value();
+		drop(position);
+
+		let first = value.floor().max(0.0) as usize;
+		let end = (value.ceil().max(0.0) as usize).min(self.count - 1);
+		let layout = *ui.layout();
+		let clip = viewport.intersect(ui.clip_rect());
+		for page in first..=end {
+			let rect = viewport.translate(Vec2::new((page as f32 - value) * viewport.width(), 0.0));
+			if !rect.intersects(viewport) {
+				continue;
+			}
+			let mut page_ui = ui.child_ui(rect, layout);
+			page_ui.set_clip_rect(clip.intersect(rect));
+			add_page(&mut ui.ui(&mut page_ui), page);
+		}
+		response
+	}
+}
+
+/// The page a swipe comes to rest on, a fast swipe moves one page even if it was short.
+pub fn settle_page(position: f32, velocity: f32, count: usize) -> usize {
+	let page = if velocity > FLING_VELOCITY {
+		position.floor() + 1.0
+	} else if velocity < -FLING_VELOCITY {
+		position.ceil() - 1.0
+	} else {
+		position.round()
+	};
+	(page.max(0.0) as usize).min(count.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn swipes_settle_on_the_nearest_or_next_page() {
+		assert_eq!(settle_page(1.4, 0.0, 5), 1);
+		assert_eq!(settle_page(1.6, 0.0, 5), 2);
+		assert_eq!(settle_page(1.2, 2.0, 5), 2);
+		assert_eq!(settle_page(1.8, -2.0, 5), 1);
+		assert_eq!(settle_page(4.1, 2.0, 5), 4);
+		assert_eq!(settle_page(-0.1, -2.0, 5), 0);
+	}
+}
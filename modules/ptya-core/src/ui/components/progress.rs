@@ -1,3 +1,4 @@
+use crate::animation::AnimationRef;
 use crate::ui::Pui;
 use egui::{lerp, vec2, Color32, Id, Painter, Pos2, Rect, Response, Sense, Shape, Stroke};
 use std::f64::consts::PI;
@@ -41,7 +42,7 @@ impl ProgressSpinner {
 	pub fn draw(self, ui: &mut Pui, rect: Rect, id: Id) {
 		let fg = ui.color().fg;
 
-		let mut animation = ui.sys().animation.get::<f32>(id);
+		let mut animation = ui.sys().animation.get_ref(AnimationRef::<f32>::new(id));
 		animation.set_to(self.progress.is_some().then(|| 2.0f32).unwrap_or(0.0));
 		let state = animation.get_value();
 
@@ -57,7 +58,7 @@ impl ProgressSpinner {
 
 			let state = ((1.0 + OVERFLOW) - state.clamp(0.0, 1.0 + OVERFLOW)) / (1.0 + OVERFLOW);
 
-			let time = ui.input().time * 1.5;
+			let time = ui.sys().animation.time() * 1.5;
 			let start = time * (PI * 2.0);
 			let end = start + 260f64.to_radians() * (time / 2.0).sin();
 
@@ -0,0 +1,149 @@
+use crate::animation::{AnimationRef, Lerp};
+use crate::color::ColorTag;
+use crate::ui::components::Text;
+use crate::ui::util::alloc_intractable;
+use crate::ui::{Pui, SPACING_SIZE};
+use egui::{Align2, FontFamily, FontId, Rect, Response, Rounding, Vec2};
+use std::ops::RangeInclusive;
+
+/// The width of the track, the value label is next to it.
+const TRACK_WIDTH: f32 = 500.0;
+const HANDLE_SIZE: f32 = 50.0;
+/// How many turns of the knob go across the whole range when there is no step.
+const KNOB_STEPS: f32 = 20.0;
+
+/// Picks a value in a range by dragging or tapping the track, like volume or temperature.
+pub struct RangeSlider<'a> {
+	pub value: &'a mut f32,
+	pub range: RangeInclusive<f32>,
+	/// The value snaps to multiples of the step from the start of the range.
+	pub step: Option<f32>,
+	pub color: ColorTag,
+	/// Shown after the value, like "°C" or "%".
+	pub suffix: String,
+	pub decimals: usize,
+}
+
+impl<'a> RangeSlider<'a> {
+	pub fn new(value: &'a mut f32, range: RangeInclusive<f32>) -> RangeSlider<'a> {
+		RangeSlider {
+			value,
+			range,
+			step: None,
+			color: ColorTag::Primary,
+			suffix: String::new(),
+			decimals: 0,
+		}
+	}
+
+	fn label(&self, value: f32) -> String {
+		format!("{value:.*}{}", self.decimals, self.suffix)
+	}
+
+	pub fn show(self, ui: &mut Pui) -> Response {
+		let font = FontId::new(40.0, FontFamily::Name("Roboto-Medium".into()));
+		// The widest label keeps the track from moving while the value changes.
+		let label_width = [*self.range.start(), *self.range.end()]
+			.map(|value| Text::new(ui, self.label(value), None, font.clone()).width())
+			.into_iter()
+			.fold(0.0, f32::max);
+
+		let (rect, mut response, rounding) = alloc_intractable(ui, TRACK_WIDTH + SPACING_SIZE + label_width);
+		let track = Rect::from_min_size(
+			rect.min + Vec2::new(SPACING_SIZE, (rect.height() - HANDLE_SIZE) / 2.0),
+			Vec2::new(TRACK_WIDTH, HANDLE_SIZE),
+		);
+
+		let focus = ui.sys().focus.register_adjustable(response.id, rect);
+		if focus.steps != 0 {
+			let step = self.step.unwrap_or((self.range.end() - self.range.start()) / KNOB_STEPS);
+			let value = snap_value(*self.value + step * focus.steps as f32, &self.range, self.step);
+			if value != *self.value {
+				*self.value = value;
+				response.mark_changed();
+			}
+		}
+
+		let dragging = response.dragged();
+		if let Some(pointer) = response.interact_pointer_pos() {
+			if dragging || response.clicked() {
+				let t = ((pointer.x - track.min.x - HANDLE_SIZE / 2.0) / (track.width() - HANDLE_SIZE)).clamp(0.0, 1.0);
+				let value = snap_value(self.range.start().lerp(self.range.end(), t), &self.range, self.step);
+				if value != *self.value {
+					*self.value = value;
+					response.mark_changed();
+				}
+			}
+		}
+
+		// Taps glide to the new value, drags follow the finger.
+		let span = self.range.end() - self.range.start();
+		let t = if span == 0.0 { 0.0 } else { (*self.value - self.range.start()) / span };
+		let mut position = ui.sys().animation.get_ref(AnimationRef::<f32>::new(response.id));
+		if dragging || ui.sys().animation.reduced_motion() {
+			position.set_value(t);
+		} else {
+			position.redirect(t);
+		}
+		let t = position.get_value();
+
+		let mut grab = ui.sys().animation.get_ref(AnimationRef::<f32>::new(response.id.with("grab")));
+		// The handle also looks grabbed while the knob adjusts it.
+		grab.redirect((dragging || focus.captured) as u8 as f32);
+		let grab = grab.get_value();
+
+		let color = ui.color().ascend(1.0);
+		let group = color.get(self.color);
+		let painter = ui.painter();
+		painter.rect_filled(rect, rounding, color.bg());
+
+		let handle = track.min.x + HANDLE_SIZE / 2.0 + (track.width() - HANDLE_SIZE) * t;
+		let full = Rounding::same(HANDLE_SIZE / 2.0);
+		painter.rect_filled(track, full, color.surface_variant);
+		painter.rect_filled(
+			Rect::from_min_max(track.min, egui::pos2(handle + HANDLE_SIZE / 2.0, track.max.y)),
+			full,
+			group.color_container,
+		);
+		painter.circle_filled(
+			egui::pos2(handle, track.center().y),
+			(HANDLE_SIZE / 2.0) * 1.0.lerp(&1.2, grab),
+			group.color,
+		);
+
+		let text = Text::new(ui, self.label(*self.value), None, font);
+		text.draw(
+			ui.painter(),
+			rect.right_center() - Vec2::new(SPACING_SIZE, 0.0),
+			Align2::RIGHT_CENTER,
+			color.fg.lerp(&group.color, grab),
+		);
+
+		response
+	}
+}
+
+/// Clamps a value into the range and snaps it to the step.
+pub fn snap_value(value: f32, range: &RangeInclusive<f32>, step: Option<f32>) -> f32 {
+	let value = value.clamp(*range.start(), *range.end());
+	match step {
+		Some(step) if step > 0.0 => {
+			let steps = ((value - range.start()) / step).round();
+			(range.start() + steps * step).min(*range.end())
+		}
+		_ => value,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn values_snap_to_steps_inside_the_range() {
+		assert_eq!(snap_value(21.3, &(16.0..=28.0), Some(0.5)), 21.5);
+		assert_eq!(snap_value(40.0, &(16.0..=28.0), Some(0.5)), 28.0);
+		assert_eq!(snap_value(9.0, &(0.0..=10.0), Some(4.0)), 8.0);
+		assert_eq!(snap_value(0.37, &(0.0..=1.0), None), 0.37);
+	}
+}
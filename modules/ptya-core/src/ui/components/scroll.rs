@@ -0,0 +1,318 @@
+use crate::animation::{AnimationRef, Spring};
+use crate::ui::gesture::GestureSense;
+use crate::ui::{Pui, SPACING_SIZE};
+use egui::epaint::{Mesh, Vertex, WHITE_UV};
+use egui::{Color32, Id, InnerResponse, Pos2, Rect, Sense, Shape, Vec2};
+use std::hash::Hash;
+use std::ops::Range;
+
+/// How long a fling keeps going, the distance is the release velocity times this.
+const FLING_TIME: f32 = 0.33;
+/// A critically damped spring that decays with the fling time, flinging at an edge overshoots and bounces back.
+const FLING: Spring = Spring::new(9.0, 6.0, 1.0);
+const FADE_SIZE: f32 = SPACING_SIZE * 2.0;
+
+#[derive(Copy, Clone, Default)]
+struct ScrollState {
+	dragging: bool,
+	// The offset the finger is at, it is rubber banded when it is past the edges.
+	raw: f32,
+	content: f32,
+}
+
+/// A vertical scroll container for touch screens.
+/// It can be flung, bounces back when pulled past the edges and fades out content at the edges.
+/// Scrolling uses [gestures](crate::ui::gesture), sideways drags go to the widgets around it.
+pub struct ScrollView {
+	id: Id,
+	/// Defaults to the available height.
+	pub height: Option<f32>,
+	pub fade: bool,
+}
+
+impl ScrollView {
+	pub fn new(id_source: impl Hash) -> ScrollView {
+		ScrollView {
+			id: Id::new(id_source),
+			height: None,
+			fade: true,
+		}
+	}
+
+	pub fn show<R>(self, ui: &mut Pui, add_contents: impl FnOnce(&mut Pui) -> R) -> InnerResponse<R> {
+		self.show_viewport(ui, None, |ui, _| add_contents(ui))
+	}
+
+	/// Shows the content with the visible part of it, relative to the top of the content.
+	/// The content height is measured from what was laid out if it is not known up front.
+	fn show_viewport<R>(
+		self,
+		ui: &mut Pui,
+		content_height: Option<f32>,
+		add_contents: impl FnOnce(&mut Pui, Rect) -> R,
+	) -> InnerResponse<R> {
+		let id = ui.make_persistent_id(self.id);
+		let size = Vec2::new(
+			ui.available_width(),
+			self.height.unwrap_or_else(|| ui.available_height()),
+		);
+		let (viewport, response) = ui.allocate_exact_size(size, Sense::hover());
+
+		let mut state: ScrollState = ui.data().get_temp(id).unwrap_or_default();
+		if let Some(height) = content_height {
+			state.content = height;
+		}
+		let max = (state.content - viewport.height()).max(0.0);
+		let offset = Self::scroll(ui, id, viewport, max, &mut state);
+
+		let layout = *ui.layout();
+		let mut content_ui = ui.child_ui(
+			Rect::from_min_size(viewport.min - Vec2::new(0.0, offset), Vec2::new(viewport.width(), f32::INFINITY)),
+			layout,
+		);
+		content_ui.set_clip_rect(viewport.intersect(ui.clip_rect()));
+		let inner = add_contents(
+			&mut ui.ui(&mut content_ui),
+			Rect::from_min_size(Pos2::new(0.0, offset), viewport.size()),
+		);
+		if content_height.is_none() {
+			state.content = content_ui.min_rect().height();
+		}
+		ui.data().insert_temp(id, state);
+		Self::follow_focus(ui, id, viewport, offset, max);
+
+		if self.fade {
+			Self::fade(ui, id, viewport, offset, max);
+		}
+
+		InnerResponse::new(inner, response)
+	}
+
+	/// The scroll offset of the scroll view with this id.
+	fn offset(id: Id) -> AnimationRef<f32> {
+		AnimationRef::new(id)
+	}
+
+	/// Handles dragging, flinging and the mouse wheel, returns the current offset.
+	fn scroll(ui: &mut Pui, id: Id, viewport: Rect, max: f32, state: &mut ScrollState) -> f32 {
+		let reduced_motion = ui.sys().animation.reduced_motion();
+		let gestures = ui.gestures(id, viewport, GestureSense::drag_y());
+		let mut animation = ui.sys().animation.get_ref(Self::offset(id));
+		let input = ui.input().clone();
+		let pointer = &input.pointer;
+
+		// Touching the content stops a fling.
+		if pointer.any_pressed() && pointer.press_origin().is_some_and(|origin| viewport.contains(origin)) {
+			let offset = animation.get_value();
+			animation.set_value(offset);
+		}
+		let delta = gestures.drag_delta();
+		if delta != Vec2::ZERO {
+			if !state.dragging {
+				state.dragging = true;
+				state.raw = animation.get_value();
+			}
+			state.raw -= delta.y;
+			animation.set_value(rubber_band(state.raw, max, viewport.height()));
+		}
+
+		if state.dragging {
+			// The finger was lifted.
+			if !pointer.any_down() {
+				state.dragging = false;
+				let offset = animation.get_value();
+				let target = fling_target(offset, -pointer.velocity().y, max);
+				if reduced_motion || (target - offset).abs() < 1.0 {
+					animation.set_value(target);
+				} else {
+					animation
+						.set_spring(FLING)
+						.set_from(offset)
+						.set_to(target)
+						.begin();
+					animation.set_velocity((-pointer.velocity().y / (target - offset)) as f64);
+				}
+			}
+		} else if input.scroll_delta.y != 0.0 && ui.rect_contains_pointer(viewport) {
+			let offset = (animation.get_value() - input.scroll_delta.y).clamp(0.0, max);
+			animation.set_value(offset);
+		} else if *animation.get_to() > max || *animation.get_to() < 0.0 {
+			// The content got smaller, or the fling was started before it did.
+			let target = animation.get_to().clamp(0.0, max);
+			if reduced_motion {
+				animation.set_value(target);
+			} else {
+				animation.set_spring(FLING).retarget(target);
+			}
+		}
+
+		if state.dragging || animation.is_active() {
+			ui.ctx().request_repaint();
+		}
+		animation.get_value()
+	}
+
+	/// Scrolls the widget that has the focus into view, so the focus can be moved through the whole content.
+	fn follow_focus(ui: &mut Pui, id: Id, viewport: Rect, offset: f32, max: f32) {
+		let focused = match ui.sys().focus.focused_rect() {
+			Some(rect) if viewport.x_range().contains(&rect.center().x) => rect,
+			_ => return,
+		};
+		let content = Rect::from_min_size(
+			viewport.min - Vec2::new(0.0, offset),
+			Vec2::new(viewport.width(), max + viewport.height()),
+		);
+		if !content.contains(focused.center()) {
+			return;
+		}
+
+		let target = if focused.top() < viewport.top() {
+			offset - (viewport.top() - focused.top())
+		} else if focused.bottom() > viewport.bottom() {
+			offset + (focused.bottom() - viewport.bottom())
+		} else {
+			return;
+		}
+		.clamp(0.0, max);
+		let mut animation = ui.sys().animation.get_ref(Self::offset(id));
+		if ui.sys().animation.reduced_motion() {
+			animation.set_value(target);
+		} else if *animation.get_to() != target {
+			animation.set_spring(FLING).retarget(target);
+		}
+	}
+
+	/// Fades the content out at the edges that have more content behind them.
+	fn fade(ui: &mut Pui, id: Id, viewport: Rect, offset: f32, max: f32) {
+		let bg = ui.color().bg();
+		let animation = &ui.sys().animation;
+		let mut top = animation.get_ref(AnimationRef::<f32>::new(id.with("fade_top")));
+		top.redirect((offset > 1.0) as u8 as f32);
+		let mut bottom = animation.get_ref(AnimationRef::<f32>::new(id.with("fade_bottom")));
+		bottom.redirect((offset < max - 1.0) as u8 as f32);
+
+		let painter = ui.painter_at(viewport);
+		let size = FADE_SIZE.min(viewport.height() / 2.0);
+		let top_rect = Rect::from_min_size(viewport.min, Vec2::new(viewport.width(), size));
+		painter.add(gradient(top_rect, bg.linear_multiply(top.get_value()), Color32::TRANSPARENT));
+		let bottom_rect = Rect::from_min_max(viewport.left_bottom() - Vec2::new(0.0, size), viewport.max);
+		painter.add(gradient(bottom_rect, Color32::TRANSPARENT, bg.linear_multiply(bottom.get_value())));
+	}
+}
+
+/// A scroll view that only lays out the rows that are visible, for lists with thousands of entries.
+/// Every row has the same height.
+pub struct VirtualList {
+	id: Id,
+	pub rows: usize,
+	pub row_height: f32,
+	/// Defaults to the available height.
+	pub height: Option<f32>,
+	pub fade: bool,
+}
+
+impl VirtualList {
+	pub fn new(id_source: impl Hash, rows: usize, row_height: f32) -> VirtualList {
+		VirtualList {
+			id: Id::new(id_source),
+			rows,
+			row_height,
+			height: None,
+			fade: true,
+		}
+	}
+
+	/// Shows the list, the row is added for every row that is visible.
+	pub fn show(self, ui: &mut Pui, mut add_row: impl FnMut(&mut Pui, usize)) -> InnerResponse<Range<usize>> {
+		let stride = self.row_height + ui.spacing().item_spacing.y;
+		let content = (self.rows as f32 * stride - ui.spacing().item_spacing.y).max(0.0);
+		let (rows, row_height) = (self.rows, self.row_height);
+		let scroll = ScrollView {
+			id: self.id,
+			height: self.height,
+			fade: self.fade,
+		};
+		scroll.show_viewport(ui, Some(content), |ui, visible| {
+			let range = visible_rows(visible.min.y, visible.height(), stride, rows);
+			let top = ui.max_rect().min;
+			for row in range.clone() {
+				let rect = Rect::from_min_size(
+					top + Vec2::new(0.0, row as f32 * stride),
+					Vec2::new(ui.available_width(), row_height),
+				);
+				ui.allocate_ui_at_rect(rect, |ui| add_row(ui, row));
+			}
+			range
+		})
+	}
+}
+
+/// Pulling past an edge moves the content less the further it goes, it never moves more than the viewport.
+pub fn rubber_band(offset: f32, max: f32, viewport: f32) -> f32 {
+	let band = |excess: f32| (1.0 - 1.0 / (excess * 0.55 / viewport + 1.0)) * viewport;
+	if offset < 0.0 {
+		-band(-offset)
+	} else if offset > max {
+		max + band(offset - max)
+	} else {
+		offset
+	}
+}
+
+/// Where a fling with a velocity in points per second comes to rest.
+pub fn fling_target(offset: f32, velocity: f32, max: f32) -> f32 {
+	(offset + velocity * FLING_TIME).clamp(0.0, max)
+}
+
+/// The rows that are at least partly visible, the offset may be past the edges while bouncing.
+pub fn visible_rows(offset: f32, viewport: f32, stride: f32, rows: usize) -> Range<usize> {
+	if stride <= 0.0 {
+		return 0..rows;
+	}
+	let start = (offset / stride).floor().max(0.0) as usize;
+	let end = ((offset + viewport) / stride).ceil().max(0.0) as usize;
+	start.min(rows)..end.min(rows)
+}
+
+fn gradient(rect: Rect, top: Color32, bottom: Color32) -> Shape {
+	let mut mesh = Mesh::default();
+	for (pos, color) in [
+		(rect.left_top(), top),
+		(rect.right_top(), top),
+		(rect.left_bottom(), bottom),
+		(rect.right_bottom(), bottom),
+	] {
+		mesh.vertices.push(Vertex { pos, uv: WHITE_UV, color });
+	}
+	mesh.add_triangle(0, 1, 2);
+	mesh.add_triangle(1, 2, 3);
+	Shape::mesh(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pulling_past_the_edges_is_resisted() {
+		assert_eq!(rubber_band(50.0, 100.0, 500.0), 50.0);
+		let pulled = rubber_band(-200.0, 100.0, 500.0);
+		assert!(pulled < 0.0 && pulled > -200.0);
+		assert!(rubber_band(100_000.0, 100.0, 500.0) < 600.0);
+	}
+
+	#[test]
+	fn flings_stop_at_the_edges() {
+		assert_eq!(fling_target(100.0, 300.0, 1000.0), 100.0 + 300.0 * FLING_TIME);
+		assert_eq!(fling_target(100.0, -3000.0, 1000.0), 0.0);
+		assert_eq!(fling_target(900.0, 3000.0, 1000.0), 1000.0);
+	}
+
+	#[test]
+	fn only_visible_rows_are_laid_out() {
+		assert_eq!(visible_rows(0.0, 250.0, 100.0, 10_000), 0..3);
+		assert_eq!(visible_rows(150.0, 250.0, 100.0, 10_000), 1..4);
+		assert_eq!(visible_rows(-80.0, 250.0, 100.0, 10_000), 0..2);
+		assert_eq!(visible_rows(950.0, 250.0, 100.0, 10), 9..10);
+	}
+}
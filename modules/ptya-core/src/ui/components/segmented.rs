@@ -0,0 +1,84 @@
+use crate::animation::Lerp;
+use crate::ui::components::tabs::{font, slide};
+use crate::ui::components::{Tab, Text};
+use crate::ui::focus::Focusable;
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE, VISUAL_SIZE};
+use egui::{Id, Pos2, Rect, Response, Rounding, Sense, Stroke, Vec2};
+use std::hash::Hash;
+
+/// The gap between the outline and the selected segment.
+const INSET: f32 = 6.0;
+
+/// A row of connected buttons where one is selected, for switching between a few views or modes.
+pub struct SegmentedButton<'a> {
+	id: Id,
+	pub selected: &'a mut usize,
+	pub segments: Vec<Tab>,
+}
+
+impl<'a> SegmentedButton<'a> {
+	pub fn new(id_source: impl Hash, selected: &'a mut usize, segments: Vec<Tab>) -> SegmentedButton<'a> {
+		SegmentedButton {
+			id: Id::new(id_source),
+			selected,
+			segments,
+		}
+	}
+
+	pub fn show(self, ui: &mut Pui) -> Response {
+		let id = ui.make_persistent_id(self.id);
+		// Every segment is as wide as the widest one so they are all easy to hit.
+		let content = self
+			.segments
+			.iter()
+			.map(|segment| {
+				let icon = segment.icon.map_or(0.0, |_| VISUAL_SIZE + SPACING_SIZE / 2.0);
+				icon + Text::new(ui, &segment.text, None, font()).width()
+			})
+			.fold(0.0, f32::max);
+		let width = content + SPACING_SIZE * 2.0;
+		let (rect, mut response) = ui.allocate_exact_size(
+			Vec2::new(width * self.segments.len() as f32, INTERACTIVE_SIZE),
+			Sense::hover(),
+		);
+		if self.segments.is_empty() {
+			return response;
+		}
+
+		let segment_rect = |i: usize| Rect::from_min_size(rect.min + Vec2::new(width * i as f32, 0.0), Vec2::new(width, rect.height()));
+		for i in 0..self.segments.len() {
+			let segment = ui.interact(segment_rect(i), id.with(i), Sense::click()).focusable(ui);
+			if segment.clicked() && *self.selected != i {
+				*self.selected = i;
+				response.mark_changed();
+			}
+		}
+		*self.selected = (*self.selected).min(self.segments.len() - 1);
+
+		let color = ui.color().ascend(1.0);
+		let rounding = color.rounding();
+		let painter = ui.painter();
+		painter.rect(rect, rounding, color.bg(), Stroke::new(2.0f32, color.outline));
+		for i in 1..self.segments.len() {
+			let x = segment_rect(i).left();
+			painter.line_segment(
+				[Pos2::new(x, rect.top() + INSET * 2.0), Pos2::new(x, rect.bottom() - INSET * 2.0)],
+				Stroke::new(2.0f32, color.outline_weak),
+			);
+		}
+
+		let indicator = slide(ui, id, rect, segment_rect(*self.selected).shrink(INSET));
+		let inner = Rounding::same((color.theme.rounding - INSET).max(0.0));
+		ui.painter().rect_filled(indicator, inner, color.secondary.color_container);
+
+		for (i, segment) in self.segments.iter().enumerate() {
+			// Segments take the selected colors while the indicator is over them.
+			let segment_rect = segment_rect(i);
+			let overlap = segment_rect.intersect(indicator).width().max(0.0) / segment_rect.width();
+			let fg = color.fg.lerp(&color.secondary.on_color_container, overlap);
+			segment.draw(ui, segment_rect.center(), fg);
+		}
+
+		response
+	}
+}
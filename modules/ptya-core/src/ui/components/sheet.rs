@@ -0,0 +1,149 @@
+use crate::animation::{AnimationRef, Spring};
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
+use egui::{Id, Rect, Rounding, Sense, Vec2};
+use std::hash::Hash;
+
+const MAX_WIDTH: f32 = 1200.0;
+const HANDLE_SIZE: Vec2 = Vec2::new(80.0, 10.0);
+/// Flinging down faster than this in points per second closes the sheet, even if it is mostly open.
+const CLOSE_VELOCITY: f32 = 1000.0;
+
+/// A sheet that slides up from the bottom of the screen, it can be dragged down by its handle to close it.
+pub struct BottomSheet {
+	id: Id,
+	pub height: f32,
+}
+
+impl BottomSheet {
+	pub fn new(id_source: impl Hash, height: f32) -> BottomSheet {
+		BottomSheet {
+			id: Id::new(id_source),
+			height,
+		}
+	}
+
+	/// How far the sheet is open, from 0 to 1.
+	fn position(&self) -> AnimationRef<f32> {
+		AnimationRef::new(self.id)
+	}
+
+	/// Shows the sheet while open is true, it is set to false when the sheet is closed.
+	/// Returns what the content returned while the sheet is visible.
+	pub fn show<R>(self, ui: &mut Pui, open: &mut bool, add_contents: impl FnOnce(&mut Pui) -> R) -> Option<R> {
+		let reduced_motion = ui.sys().animation.reduced_motion();
+		let mut position = ui.sys().animation.get_ref(self.position());
+		if !ui.memory().is_being_dragged(self.id.with("handle")) {
+			position.set_spring(Spring::SNAPPY);
+			if reduced_motion {
+				position.set_value(*open as u8 as f32);
+			} else {
+				position.redirect(*open as u8 as f32);
+			}
+		}
+		let shown = position.get_value();
+		drop(position);
+		if shown <= 0.0 && !*open {
+			return None;
+		}
+
+		let screen = ui.ctx().input().screen_rect();
+		let color = ui.color().dialog();
+		let height = self.height.min(screen.height() - SPACING_SIZE);
+		let width = screen.width().min(MAX_WIDTH);
+		let sheet = Rect::from_min_size(
+			screen.center_bottom() - Vec2::new(width / 2.0, height * shown),
+			Vec2::new(width, height),
+		);
+		let handle = Rect::from_min_size(sheet.min, Vec2::new(width, INTERACTIVE_SIZE));
+
+		let inner = ui.overlay(self.id.with("overlay"), screen, |ui| {
+			ui.color = color;
+			ui.sys().focus.trap(sheet);
+			let scrim = ui.interact(screen, self.id.with("scrim"), Sense::click());
+			if scrim.clicked() && scrim.interact_pointer_pos().is_some_and(|pos| !sheet.contains(pos)) {
+				*open = false;
+			}
+
+			let drag = ui.interact(handle, self.id.with("handle"), Sense::drag());
+			let mut position = ui.sys().animation.get_ref(self.position());
+			if drag.dragged() {
+				let shown = (shown - drag.drag_delta().y / height).clamp(0.0, 1.0);
+				position.set_value(shown);
+			} else if drag.drag_released() {
+				let velocity = ui.input().pointer.velocity().y;
+				if !stays_open(shown, velocity) {
+					*open = false;
+				}
+				let target = *open as u8 as f32;
+				if reduced_motion || target == shown {
+					position.set_value(target);
+				} else {
+					// The sheet keeps the speed of the finger.
+					position
+						.set_spring(Spring::SNAPPY)
+						.retarget(target)
+						.set_velocity(release_velocity(shown, target, velocity, height));
+				}
+			}
+			drop(position);
+
+			let rounding = Rounding {
+				nw: color.theme.rounding,
+				ne: color.theme.rounding,
+				sw: 0.0,
+				se: 0.0,
+			};
+			let painter = ui.painter();
+			painter.rect_filled(screen, 0.0, color.scrim.linear_multiply(shown));
+			painter.rect_filled(sheet, rounding, color.bg());
+			painter.rect_filled(
+				Rect::from_center_size(handle.center(), HANDLE_SIZE),
+				Rounding::same(HANDLE_SIZE.y / 2.0),
+				color.outline,
+			);
+
+			let content = Rect::from_min_max(handle.left_bottom(), sheet.max).shrink2(Vec2::new(SPACING_SIZE, 0.0));
+			let layout = *ui.layout();
+			let mut content_ui = ui.child_ui(content, layout);
+			content_ui.set_clip_rect(content.intersect(screen));
+			add_contents(&mut ui.ui(&mut content_ui))
+		});
+		Some(inner.inner)
+	}
+}
+
+/// Checks if a sheet that is let go stays open, `velocity` is how fast it was moving down in points per second.
+pub fn stays_open(shown: f32, velocity: f32) -> bool {
+	shown >= 0.5 && velocity <= CLOSE_VELOCITY
+}
+
+/// Converts the speed of the finger in points per second to progress per second towards the target.
+pub fn release_velocity(shown: f32, target: f32, velocity: f32, height: f32) -> f64 {
+	if target == shown {
+		return 0.0;
+	}
+	(-velocity / height / (target - shown)) as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sheets_close_when_flung_or_mostly_closed() {
+		assert!(stays_open(0.8, 0.0));
+		assert!(stays_open(0.5, -3000.0));
+		assert!(!stays_open(0.4, 0.0));
+		assert!(!stays_open(0.9, CLOSE_VELOCITY + 1.0));
+	}
+
+	#[test]
+	fn sheets_keep_the_speed_of_the_finger() {
+		// Half open in a 1000 point sheet, the finger moves 500 points per second.
+		assert_eq!(release_velocity(0.5, 1.0, -500.0, 1000.0), 1.0);
+		assert_eq!(release_velocity(0.5, 0.0, 500.0, 1000.0), 1.0);
+		// Moving away from the target.
+		assert_eq!(release_velocity(0.5, 0.0, -500.0, 1000.0), -1.0);
+		assert_eq!(release_velocity(1.0, 1.0, 500.0, 1000.0), 0.0);
+	}
+}
@@ -1,4 +1,4 @@
-use crate::animation::{extend, Easing, Lerp};
+use crate::animation::{extend, AnimationRef, Easing, Lerp};
 use crate::color::ColorTag;
 use crate::ui::components::Text;
 use crate::ui::util::{alloc_intractable, draw_icon};
@@ -47,12 +47,13 @@ impl Slider {
 			(current_pos / DISTANCE).clamp(if decline_allowed { -1.0 } else { 0.0 }, 1.0);
 
 		let ani = &ui.sys().animation;
-		let mut press_ani = ani.get(id.with("press"));
-		let mut progress_ani = ani.get(id);
+		let mut press_ani = ani.get_ref(AnimationRef::<f32>::new(id.with("press")));
+		let mut progress_ani = ani.get_ref(AnimationRef::<f32>::new(id));
 
 		if response.drag_released() {
 			let mut speed = 1.0;
-			if slide_pos.abs() >= 1.0 {
+			// The press bounce is only decoration, so it is left out with reduced motion.
+			if slide_pos.abs() >= 1.0 && !ani.reduced_motion() {
 				press_ani
 					.set_easing(Easing::EaseOut)
 					.set_from(slide_pos.clamp(-1.0, 1.0))
@@ -101,6 +102,8 @@ impl Slider {
 
 		let (rect, response, rounding) =
 			alloc_intractable(ui, decline_size + text.width() + accept_size);
+		// Like sliding, the knob has to be pressed and then turned so it can not confirm by accident.
+		let focus = ui.sys().focus.register_adjustable(response.id, rect);
 
 		// Slider math
 		let (slide_pos, press_slide_phone) =
@@ -210,12 +213,18 @@ impl Slider {
 			Stroke::none(),
 		);
 
-		// Icons
+		// Icons, with icon cues the idle icons say what the side does instead of only where to slide.
+		let icon_cues = ui.sys().color.icon_cues();
+		let (decline_icon, accept_icon) = if icon_cues {
+			(icon!("close"), icon!("done"))
+		} else {
+			(icon!("keyboard_double_arrow_left"), icon!("keyboard_double_arrow_right"))
+		};
 		let stuff_pos = slide_pos.abs();
 		if self.decline_allowed {
 			draw_icon(
 				painter,
-				icon!("keyboard_double_arrow_left"),
+				decline_icon,
 				decline_pos + item_offset,
 				VISUAL_SIZE,
 				color
@@ -239,7 +248,7 @@ impl Slider {
 		{
 			draw_icon(
 				painter,
-				icon!("keyboard_double_arrow_right"),
+				accept_icon,
 				accept_pos + item_offset,
 				VISUAL_SIZE,
 				color
@@ -288,6 +297,14 @@ impl Slider {
 				decline = true;
 			}
 		}
+		if focus.steps > 0 {
+			confirm = true;
+		} else if focus.steps < 0 && self.decline_allowed {
+			decline = true;
+		}
+		if confirm || decline {
+			ui.sys().focus.release();
+		}
 
 		SliderResponse {
 			response,
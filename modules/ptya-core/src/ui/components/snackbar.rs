@@ -0,0 +1,119 @@
+use crate::animation::{AnimationRef, Lerp};
+use crate::ui::components::Text;
+use crate::ui::focus::Focusable;
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
+use egui::{Align2, FontFamily, FontId, Id, Rect, Sense, Vec2};
+use std::hash::Hash;
+
+const MIN_WIDTH: f32 = 400.0;
+
+/// A short message at the bottom of the screen that goes away by itself, like "Route deleted" with an undo action.
+/// It does not block the content behind it.
+pub struct Snackbar {
+	id: Id,
+	pub text: String,
+	pub action: Option<String>,
+	/// How long the snackbar is shown in seconds.
+	pub duration: f64,
+}
+
+impl Snackbar {
+	pub fn new(id_source: impl Hash, text: impl Into<String>) -> Snackbar {
+		Snackbar {
+			id: Id::new(id_source),
+			text: text.into(),
+			action: None,
+			duration: 4.0,
+		}
+	}
+
+	pub fn with_action(id_source: impl Hash, text: impl Into<String>, action: impl Into<String>) -> Snackbar {
+		Snackbar {
+			action: Some(action.into()),
+			..Snackbar::new(id_source, text)
+		}
+	}
+
+	/// Shows the snackbar while open is true, it is set to false when the time is up or the action is used.
+	pub fn show(self, ui: &mut Pui, open: &mut bool) -> SnackbarResponse {
+		let mut response = SnackbarResponse::default();
+
+		// The time it was opened, so it is shown for the whole duration after being opened again.
+		let time = ui.sys().animation.time();
+		if *open {
+			let opened = *ui.data().get_temp_mut_or(self.id, time);
+			if time - opened > self.duration {
+				*open = false;
+			}
+		}
+		if !*open {
+			ui.data().remove::<f64>(self.id);
+		}
+
+		let mut animation = ui.sys().animation.get_ref(AnimationRef::<f32>::new(self.id));
+		if ui.sys().animation.reduced_motion() {
+			animation.set_value(*open as u8 as f32);
+		} else {
+			animation.redirect(*open as u8 as f32);
+		}
+		let shown = animation.get_value();
+		drop(animation);
+		if shown == 0.0 {
+			return response;
+		}
+		// The timer needs frames to run out.
+		ui.ctx().request_repaint();
+
+		let screen = ui.ctx().input().screen_rect();
+		let color = ui.color();
+		let font = FontId::new(35.0, FontFamily::Name("Roboto-Regular".into()));
+		let text = Text::new(ui, &self.text, None, font);
+		let action = self.action.as_ref().map(|action| {
+			Text::new(ui, action, None, FontId::new(35.0, FontFamily::Name("Roboto-Medium".into())))
+		});
+		let action_width = action.as_ref().map_or(0.0, |action| action.width() + SPACING_SIZE * 2.0);
+		let width = (SPACING_SIZE + text.width() + SPACING_SIZE + action_width)
+			.max(MIN_WIDTH)
+			.min(screen.width() - SPACING_SIZE * 2.0);
+		// Slides up from below the screen.
+		let bottom = screen.bottom() + INTERACTIVE_SIZE;
+		let y = bottom.lerp(&(screen.bottom() - SPACING_SIZE - INTERACTIVE_SIZE), shown);
+		let rect = Rect::from_min_size(egui::pos2(screen.center().x - width / 2.0, y), Vec2::new(width, INTERACTIVE_SIZE));
+
+		ui.overlay(self.id.with("overlay"), rect, |ui| {
+			let painter = ui.painter();
+			painter.rect_filled(rect, color.rounding(), color.inverse_surface);
+			text.draw(
+				painter,
+				rect.left_center() + Vec2::new(SPACING_SIZE, 0.0),
+				Align2::LEFT_CENTER,
+				color.inverse_on_surface,
+			);
+
+			if let Some(action) = action {
+				let area = Rect::from_min_max(rect.right_top() - Vec2::new(action_width, 0.0), rect.max);
+				if ui.interact(area, self.id.with("action"), Sense::click()).focusable(ui).clicked() {
+					response.action = true;
+				}
+				action.draw(ui.painter(), area.center(), Align2::CENTER_CENTER, color.inverse_primary);
+			}
+		});
+
+		if response.action {
+			*open = false;
+		}
+		response
+	}
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct SnackbarResponse {
+	action: bool,
+}
+
+impl SnackbarResponse {
+	/// Checks if the action was used, like undo.
+	pub fn action(&self) -> bool {
+		self.action
+	}
+}
@@ -0,0 +1,125 @@
+use crate::animation::{AnimationImpl, AnimationRef};
+use crate::ui::components::Text;
+use crate::ui::focus::Focusable;
+use crate::ui::util::draw_icon;
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE, VISUAL_SIZE};
+use egui::{Align2, FontFamily, FontId, Id, Pos2, Rect, Response, Rounding, Sense, Vec2};
+use std::hash::Hash;
+
+const INDICATOR_HEIGHT: f32 = 6.0;
+
+/// A tab in a [TabBar] or a segment in a [SegmentedButton](crate::ui::components::SegmentedButton).
+#[derive(Clone)]
+pub struct Tab {
+	pub text: String,
+	/// Code point of an icon, see [draw_icon].
+	pub icon: Option<u32>,
+}
+
+impl Tab {
+	pub fn new(text: impl Into<String>) -> Tab {
+		Tab {
+			text: text.into(),
+			icon: None,
+		}
+	}
+
+	pub fn with_icon(icon: u32, text: impl Into<String>) -> Tab {
+		Tab {
+			text: text.into(),
+			icon: Some(icon),
+		}
+	}
+
+	/// Draws the icon and text centered on a position, returns how wide they are.
+	pub(crate) fn draw(&self, ui: &Pui, center: Pos2, color: egui::Color32) -> f32 {
+		let text = Text::new(ui, &self.text, None, font());
+		let icon_width = self.icon.map_or(0.0, |_| VISUAL_SIZE + SPACING_SIZE / 2.0);
+		let width = icon_width + text.width();
+		let left = center.x - width / 2.0;
+		if let Some(icon) = self.icon {
+			draw_icon(ui.painter(), icon, Pos2::new(left + VISUAL_SIZE / 2.0, center.y), VISUAL_SIZE, color);
+		}
+		text.draw(ui.painter(), Pos2::new(left + icon_width, center.y), Align2::LEFT_CENTER, color);
+		width
+	}
+}
+
+/// Tabs across the full width with an indicator that slides to the selected tab.
+pub struct TabBar<'a> {
+	id: Id,
+	pub selected: &'a mut usize,
+	pub tabs: Vec<Tab>,
+}
+
+impl<'a> TabBar<'a> {
+	pub fn new(id_source: impl Hash, selected: &'a mut usize, tabs: Vec<Tab>) -> TabBar<'a> {
+		TabBar {
+			id: Id::new(id_source),
+			selected,
+			tabs,
+		}
+	}
+
+	pub fn show(self, ui: &mut Pui) -> Response {
+		let id = ui.make_persistent_id(self.id);
+		let size = Vec2::new(ui.available_width(), INTERACTIVE_SIZE);
+		let (rect, mut response) = ui.allocate_exact_size(size, Sense::hover());
+		if self.tabs.is_empty() {
+			return response;
+		}
+
+		let width = rect.width() / self.tabs.len() as f32;
+		let tab_rect = |i: usize| Rect::from_min_size(rect.min + Vec2::new(width * i as f32, 0.0), Vec2::new(width, rect.height()));
+		for i in 0..self.tabs.len() {
+			let tab = ui.interact(tab_rect(i), id.with(i), Sense::click()).focusable(ui);
+			if tab.clicked() && *self.selected != i {
+				*self.selected = i;
+				response.mark_changed();
+			}
+		}
+		*self.selected = (*self.selected).min(self.tabs.len() - 1);
+
+		let color = ui.color();
+		ui.painter().line_segment(
+			[rect.left_bottom(), rect.right_bottom()],
+			(1.0, color.outline_weak),
+		);
+
+		let mut widths = Vec::with_capacity(self.tabs.len());
+		for (i, tab) in self.tabs.iter().enumerate() {
+			let selected = i == *self.selected;
+			let fg = if selected { color.primary.color } else { color.on_surface_variant };
+			widths.push(tab.draw(ui, tab_rect(i).center(), fg));
+		}
+
+		// The indicator is as wide as the content of the tab and stretches while it moves.
+		let selected = tab_rect(*self.selected);
+		let target = Rect::from_center_size(
+			Pos2::new(selected.center().x, selected.bottom() - INDICATOR_HEIGHT / 2.0),
+			Vec2::new(widths[*self.selected] + SPACING_SIZE, INDICATOR_HEIGHT),
+		);
+		let indicator = slide(ui, id, rect, target);
+		ui.painter().rect_filled(indicator, Rounding::same(INDICATOR_HEIGHT / 2.0), color.primary.color);
+
+		response
+	}
+}
+
+/// Moves a selection indicator to its target, it starts at the target the first time it is shown.
+/// The indicator moves relative to the bar it is in, so it stays in place when the bar itself moves.
+pub(crate) fn slide(ui: &Pui, id: Id, bar: Rect, target: Rect) -> Rect {
+	let origin = bar.min.to_vec2();
+	let target = target.translate(-origin);
+	let mut indicator = ui.sys().animation.get_ref_or(AnimationRef::new(id), || AnimationImpl::simple(target));
+	if ui.sys().animation.reduced_motion() {
+		indicator.set_value(target);
+	} else if *indicator.get_to() != target {
+		indicator.retarget(target);
+	}
+	indicator.get_value().translate(origin)
+}
+
+pub(crate) fn font() -> FontId {
+	FontId::new(35.0, FontFamily::Name("Roboto-Medium".into()))
+}
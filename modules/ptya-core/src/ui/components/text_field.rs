@@ -0,0 +1,155 @@
+use crate::animation::{AnimationRef, Lerp};
+use crate::keyboard::{apply_input, KeyInput, KeyboardLayout};
+use crate::ui::components::Text;
+use crate::ui::focus::Focusable;
+use crate::ui::util::alloc_intractable;
+use crate::ui::{Pui, SPACING_SIZE};
+use egui::{Align2, Event, FontFamily, FontId, Key, Pos2, Response, RichText, Stroke, Vec2, WidgetText};
+use std::ops::Deref;
+
+/// The width of the text area of an empty field.
+const MIN_WIDTH: f32 = 400.0;
+
+pub struct TextField<'a> {
+	pub text: &'a mut String,
+	/// Shown while the field is empty.
+	pub hint: String,
+	/// The layout the on-screen keyboard opens with.
+	pub layout: KeyboardLayout,
+	/// Hides the text and keeps it out of the keyboard suggestions.
+	pub password: bool,
+}
+
+impl<'a> TextField<'a> {
+	pub fn new(text: &'a mut String, hint: impl Into<String>) -> TextField<'a> {
+		TextField {
+			text,
+			hint: hint.into(),
+			layout: KeyboardLayout::Qwerty,
+			password: false,
+		}
+	}
+
+	/// Collects typing from a hardware keyboard, some head units have one connected while testing.
+	fn hardware_input(ui: &Pui) -> Vec<KeyInput> {
+		ui.input()
+			.events
+			.iter()
+			.filter_map(|event| match event {
+				Event::Text(text) => Some(text.chars().map(KeyInput::Char).collect()),
+				Event::Key { key: Key::Backspace, pressed: true, .. } => Some(vec![KeyInput::Backspace]),
+				Event::Key { key: Key::Enter, pressed: true, .. } => Some(vec![KeyInput::Submit]),
+				_ => None,
+			})
+			.flatten()
+			.collect()
+	}
+
+	fn shown_text(&self) -> String {
+		if self.text.is_empty() {
+			self.hint.clone()
+		} else if self.password {
+			"•".repeat(self.text.chars().count())
+		} else {
+			self.text.clone()
+		}
+	}
+
+	pub fn show(self, ui: &mut Pui) -> TextFieldResponse {
+		let font = FontId::new(40.0, FontFamily::Name("Roboto-Regular".into()));
+		let width = Text::new(ui, self.shown_text(), None, font.clone()).width();
+		let (rect, response, rounding) = alloc_intractable(ui, width.max(MIN_WIDTH));
+		let response = response.focusable(ui);
+
+		let keyboard = &ui.sys().keyboard;
+		if response.clicked() {
+			keyboard.open(response.id, self.layout, self.password);
+		}
+		let focused = keyboard.is_target(response.id);
+
+		// Typing shows up right away, the field only grows to fit it on the next frame.
+		let mut changed = false;
+		let mut submitted = false;
+		if focused {
+			let mut input = keyboard.take_input(response.id, rect);
+			input.extend(Self::hardware_input(ui));
+			let before = self.text.clone();
+			submitted = apply_input(self.text, input);
+			changed = *self.text != before;
+			// Passwords are kept out of the suggestions, the keyboard gets an empty text for them.
+			let text = if self.password { "" } else { self.text.as_str() };
+			if submitted {
+				keyboard.submit(text);
+			} else {
+				keyboard.set_text(response.id, text);
+			}
+		}
+
+		let empty = self.text.is_empty();
+		let text = Text::new(ui, WidgetText::RichText(RichText::new(self.shown_text())), None, font);
+
+		let mut focus = ui.sys().animation.get_ref(AnimationRef::<f32>::new(response.id.with("focus")));
+		focus.redirect(focused as u8 as f32);
+		let focus = focus.get_value();
+
+		let color = ui.color().ascend(1.0);
+		let painter = ui.painter();
+		painter.rect(
+			rect,
+			rounding,
+			color.bg(),
+			Stroke::new(2.0f32.lerp(&4.0, focus), color.outline.lerp(&color.primary.color, focus)),
+		);
+
+		let text_pos = rect.left_center() + Vec2::new(SPACING_SIZE + 5.0, 0.0);
+		let fg = if empty { color.on_surface_variant } else { color.fg };
+		text.draw(painter, text_pos, Align2::LEFT_CENTER, fg);
+
+		// The cursor is always at the end, blinking like most text fields.
+		if focused {
+			let time = ui.sys().animation.time();
+			if time.fract() < 0.5 {
+				let x = if empty { text_pos.x } else { text_pos.x + text.width() + 2.0 };
+				let height = text.height() * 0.4;
+				painter.line_segment(
+					[Pos2::new(x, rect.center().y - height), Pos2::new(x, rect.center().y + height)],
+					Stroke::new(3.0f32, color.primary.color),
+				);
+			}
+			ui.ctx().request_repaint();
+		}
+
+		TextFieldResponse {
+			response,
+			changed,
+			submitted,
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct TextFieldResponse {
+	pub response: Response,
+	changed: bool,
+	submitted: bool,
+}
+
+impl Deref for TextFieldResponse {
+	type Target = Response;
+
+	fn deref(&self) -> &Self::Target {
+		&self.response
+	}
+}
+
+impl TextFieldResponse {
+	/// Checks if the text was edited this frame.
+	pub fn changed(&self) -> bool {
+		self.changed
+	}
+
+	/// Checks if enter was pressed, the keyboard closes when it is.
+	pub fn submitted(&self) -> bool {
+		self.submitted
+	}
+}
@@ -0,0 +1,93 @@
+use crate::animation::{AnimationImpl, AnimationRef, Lerp};
+use crate::color::ColorTag;
+use crate::ui::components::Text;
+use crate::ui::focus::Focusable;
+use crate::ui::util::{alloc_intractable, draw_icon};
+use crate::ui::{Pui, SPACING_SIZE};
+use egui::{Align2, FontFamily, FontId, Rect, Response, RichText, Rounding, Stroke, Vec2, WidgetText};
+use ptya_icon::icon;
+
+const SWITCH_SIZE: Vec2 = Vec2::new(104.0, 56.0);
+
+/// A switch for settings that are on or off.
+pub struct Toggle<'a> {
+	pub value: &'a mut bool,
+	pub text: WidgetText,
+	pub color: ColorTag,
+}
+
+impl<'a> Toggle<'a> {
+	pub fn new(value: &'a mut bool, text: impl Into<String>) -> Toggle<'a> {
+		Toggle {
+			value,
+			text: WidgetText::RichText(RichText::new(text).strong()),
+			color: ColorTag::Primary,
+		}
+	}
+
+	pub fn show(self, ui: &mut Pui) -> Response {
+		let text = Text::new(
+			ui,
+			self.text,
+			None,
+			FontId::new(40.0, FontFamily::Name("Roboto-Medium".into())),
+		);
+
+		let (rect, response, rounding) = alloc_intractable(ui, text.width() + SPACING_SIZE + SWITCH_SIZE.x);
+		let mut response = response.focusable(ui);
+		if response.clicked() {
+			*self.value = !*self.value;
+			response.mark_changed();
+		}
+
+		// A switch that is shown for the first time starts where it is instead of sliding in from off.
+		let value = *self.value as u8 as f32;
+		let mut animation = ui
+			.sys()
+			.animation
+			.get_ref_or(AnimationRef::new(response.id), || AnimationImpl::simple(value));
+		if ui.sys().animation.reduced_motion() {
+			animation.set_value(value);
+		} else {
+			animation.redirect_with_speed(value, 2.0);
+		}
+		let on = animation.get_value();
+
+		let color = ui.color().ascend(1.0);
+		let group = color.get(self.color);
+		let painter = ui.painter();
+		painter.rect_filled(rect, rounding, color.bg());
+		text.draw(
+			painter,
+			rect.left_center() + Vec2::new(SPACING_SIZE, 0.0),
+			Align2::LEFT_CENTER,
+			color.fg,
+		);
+
+		let switch = Rect::from_center_size(
+			rect.right_center() - Vec2::new(SPACING_SIZE + SWITCH_SIZE.x / 2.0, 0.0),
+			SWITCH_SIZE,
+		);
+		let pill = Rounding::same(SWITCH_SIZE.y / 2.0);
+		painter.rect(
+			switch,
+			pill,
+			color.surface_container_highest.lerp(&group.color, on),
+			Stroke::new(2.0 * (1.0 - on), color.outline),
+		);
+
+		// The thumb grows when on, like material switches, and shows an icon so the state does not rely on color.
+		let travel = SWITCH_SIZE.x - SWITCH_SIZE.y;
+		let center = switch.left_center() + Vec2::new(SWITCH_SIZE.y / 2.0 + travel * on, 0.0);
+		let radius = SWITCH_SIZE.y * 0.3.lerp(&0.42, on);
+		painter.circle_filled(center, radius, color.outline.lerp(&group.on_color, on));
+		let (icon, icon_color) = if on > 0.5 {
+			(icon!("check"), group.on_color_container)
+		} else {
+			(icon!("close"), color.surface_container_highest)
+		};
+		draw_icon(painter, icon, center, radius * 1.3, icon_color);
+
+		response
+	}
+}
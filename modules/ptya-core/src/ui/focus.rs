@@ -0,0 +1,333 @@
+//! Focus navigation for rotary controllers and steering wheel buttons.
+//! Widgets that can be focused register every frame with [Focusable::focusable],
+//! the focus moves between them with [FocusInput]s that are read from key events,
+//! so a keyboard works the same as the controller in the car.
+//! Arrows move to the closest widget in that direction, turning the knob goes through them in order.
+//! Adjustable widgets like sliders capture the knob when activated until they are activated again.
+use crate::ui::Pui;
+use egui::mutex::Mutex;
+use egui::{Context, Event, Id, Key, Modifiers, PointerButton, Rect, Response, Vec2};
+
+/// Input for moving the focus, controllers send these as key presses.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FocusInput {
+	Up,
+	Down,
+	Left,
+	Right,
+	/// Turning the knob clockwise.
+	Next,
+	/// Turning the knob counterclockwise.
+	Previous,
+	/// Pressing the knob.
+	Activate,
+	Back,
+}
+
+impl FocusInput {
+	pub fn from_key(key: Key, modifiers: Modifiers) -> Option<FocusInput> {
+		Some(match key {
+			Key::ArrowUp => FocusInput::Up,
+			Key::ArrowDown => FocusInput::Down,
+			Key::ArrowLeft => FocusInput::Left,
+			Key::ArrowRight => FocusInput::Right,
+			Key::Tab if modifiers.shift => FocusInput::Previous,
+			Key::Tab => FocusInput::Next,
+			// Rotary controllers usually show up as page keys.
+			Key::PageDown => FocusInput::Next,
+			Key::PageUp => FocusInput::Previous,
+			Key::Enter | Key::Space => FocusInput::Activate,
+			Key::Escape => FocusInput::Back,
+			_ => return None,
+		})
+	}
+
+	/// How an adjustable widget moves for this input.
+	fn step(self) -> i32 {
+		match self {
+			FocusInput::Up | FocusInput::Right | FocusInput::Next => 1,
+			FocusInput::Down | FocusInput::Left | FocusInput::Previous => -1,
+			FocusInput::Activate | FocusInput::Back => 0,
+		}
+	}
+}
+
+/// Reads the focus input from the key presses of this frame.
+pub fn inputs(ctx: &Context) -> Vec<FocusInput> {
+	ctx.input()
+		.events
+		.iter()
+		.filter_map(|event| match event {
+			Event::Key { key, pressed: true, modifiers } => FocusInput::from_key(*key, *modifiers),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Lets a widget be focused with keys and a rotary controller.
+pub trait Focusable: Sized {
+	/// Registers the widget for this frame, activating it counts as a click.
+	fn focusable(self, ui: &Pui) -> Self {
+		self.focus(ui).0
+	}
+
+	/// Like [Focusable::focusable] but also returns the focus state,
+	/// for widgets that only react to the knob and not to a tap.
+	fn focus(self, ui: &Pui) -> (Self, FocusResponse);
+}
+
+impl Focusable for Response {
+	fn focus(mut self, ui: &Pui) -> (Response, FocusResponse) {
+		// egui has its own tab focus that also clicks on enter, that would click twice.
+		ui.memory().surrender_focus(self.id);
+		let focus = ui.sys().focus.register(self.id, self.rect);
+		if focus.activated {
+			self.clicked[PointerButton::Primary as usize] = true;
+		}
+		(self, focus)
+	}
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct FocusResponse {
+	/// The widget has the focus and the focus ring is shown.
+	pub focused: bool,
+	pub activated: bool,
+	/// Adjustable widgets only, the knob is turned while the widget has captured it.
+	pub captured: bool,
+	/// How far the value was moved up or down while captured.
+	pub steps: i32,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Target {
+	id: Id,
+	rect: Rect,
+	adjustable: bool,
+}
+
+/// The focused widget, shared by all widgets like the [Keyboard](crate::keyboard::Keyboard).
+/// Input is handled at the end of the frame and the widget picks it up on the next frame.
+#[derive(Default)]
+pub struct Focus {
+	inner: Mutex<FocusState>,
+}
+
+#[derive(Default)]
+struct FocusState {
+	focused: Option<Id>,
+	// The focus ring only shows after a key was pressed, touching the screen hides it again.
+	visible: bool,
+	captured: bool,
+	activated: bool,
+	steps: i32,
+	targets: Vec<Target>,
+	modal: Option<Rect>,
+}
+
+impl Focus {
+	pub fn register(&self, id: Id, rect: Rect) -> FocusResponse {
+		self.add(Target { id, rect, adjustable: false })
+	}
+
+	/// Registers a widget that is adjusted with the knob instead of only being activated.
+	pub fn register_adjustable(&self, id: Id, rect: Rect) -> FocusResponse {
+		self.add(Target { id, rect, adjustable: true })
+	}
+
+	fn add(&self, target: Target) -> FocusResponse {
+		let mut inner = self.inner.lock();
+		inner.targets.push(target);
+		if inner.focused != Some(target.id) {
+			return FocusResponse::default();
+		}
+		FocusResponse {
+			focused: inner.visible,
+			activated: std::mem::take(&mut inner.activated),
+			captured: inner.captured && target.adjustable,
+			steps: std::mem::take(&mut inner.steps),
+		}
+	}
+
+	/// Keeps the focus inside a modal overlay while it is shown this frame.
+	pub fn trap(&self, rect: Rect) {
+		self.inner.lock().modal = Some(rect);
+	}
+
+	/// Lets go of the knob after an adjustable widget is done with it.
+	pub fn release(&self) {
+		self.inner.lock().captured = false;
+	}
+
+	pub fn is_captured(&self) -> bool {
+		self.inner.lock().captured
+	}
+
+	/// Where the focus ring goes, [None] while it is hidden.
+	pub fn focused_rect(&self) -> Option<Rect> {
+		let inner = self.inner.lock();
+		if !inner.visible {
+			return None;
+		}
+		inner
+			.targets
+			.iter()
+			.find(|target| Some(target.id) == inner.focused)
+			.map(|target| target.rect)
+	}
+
+	/// Moves the focus with the input of this frame, call it after the ui is drawn.
+	pub fn end_frame(&self, inputs: &[FocusInput], pointer_pressed: bool) {
+		let mut inner = self.inner.lock();
+		let modal = inner.modal.take();
+		let targets: Vec<_> = std::mem::take(&mut inner.targets)
+			.into_iter()
+			.filter(|target| modal.is_none_or(|modal| modal.contains(target.rect.center())))
+			.collect();
+		inner.activated = false;
+		inner.steps = 0;
+		if pointer_pressed {
+			inner.visible = false;
+			inner.captured = false;
+		}
+
+		// The widget went away or is behind a modal now.
+		if !targets.iter().any(|target| Some(target.id) == inner.focused) {
+			inner.captured = false;
+			inner.focused = if inner.visible { targets.first().map(|target| target.id) } else { None };
+		}
+
+		for &input in inputs {
+			if !inner.visible {
+				// The first key only shows where the focus is.
+				inner.visible = input != FocusInput::Back;
+				if inner.focused.is_none() {
+					inner.focused = targets.first().map(|target| target.id);
+				}
+				continue;
+			}
+
+			let adjustable = targets
+				.iter()
+				.find(|target| Some(target.id) == inner.focused)
+				.is_some_and(|target| target.adjustable);
+			match input {
+				FocusInput::Back if inner.captured => inner.captured = false,
+				FocusInput::Back => inner.visible = false,
+				FocusInput::Activate if adjustable => inner.captured = !inner.captured,
+				FocusInput::Activate => inner.activated = inner.focused.is_some(),
+				_ if inner.captured => inner.steps += input.step(),
+				_ => {
+					if let Some(next) = navigate(&targets, inner.focused, input) {
+						inner.focused = Some(next);
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Finds the widget the focus moves to, arrows go to the closest widget in their direction.
+fn navigate(targets: &[Target], from: Option<Id>, input: FocusInput) -> Option<Id> {
+	let index = match from.and_then(|id| targets.iter().position(|target| target.id == id)) {
+		Some(index) => index,
+		None => return targets.first().map(|target| target.id),
+	};
+	let len = targets.len();
+	let direction = match input {
+		FocusInput::Next => return Some(targets[(index + 1) % len].id),
+		FocusInput::Previous => return Some(targets[(index + len - 1) % len].id),
+		FocusInput::Up => Vec2::new(0.0, -1.0),
+		FocusInput::Down => Vec2::new(0.0, 1.0),
+		FocusInput::Left => Vec2::new(-1.0, 0.0),
+		FocusInput::Right => Vec2::new(1.0, 0.0),
+		FocusInput::Activate | FocusInput::Back => return None,
+	};
+
+	let from = targets[index].rect.center();
+	targets
+		.iter()
+		.enumerate()
+		.filter(|(i, _)| *i != index)
+		.filter_map(|(_, target)| {
+			let offset = target.rect.center() - from;
+			let along = offset.dot(direction);
+			if along <= 0.0 {
+				return None;
+			}
+			// Widgets that are off to the side are further away, so moving down a column stays in it.
+			let across = (offset - direction * along).length();
+			Some((target.id, along + across * 2.0))
+		})
+		.min_by(|(_, a), (_, b)| a.total_cmp(b))
+		.map(|(id, _)| id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use egui::Pos2;
+
+	fn target(name: &str, x: f32, y: f32) -> Target {
+		Target {
+			id: Id::new(name),
+			rect: Rect::from_center_size(Pos2::new(x, y), Vec2::splat(50.0)),
+			adjustable: false,
+		}
+	}
+
+	#[test]
+	fn arrows_go_to_the_closest_widget_in_their_direction() {
+		// A sidebar with two entries next to a column of two widgets.
+		let targets = [
+			target("a", 0.0, 0.0),
+			target("b", 0.0, 100.0),
+			target("c", 300.0, 20.0),
+			target("d", 300.0, 300.0),
+		];
+		let id = |name: &str| Some(Id::new(name));
+		assert_eq!(navigate(&targets, None, FocusInput::Down), id("a"));
+		assert_eq!(navigate(&targets, id("a"), FocusInput::Down), id("b"));
+		assert_eq!(navigate(&targets, id("a"), FocusInput::Right), id("c"));
+		assert_eq!(navigate(&targets, id("c"), FocusInput::Down), id("d"));
+		assert_eq!(navigate(&targets, id("d"), FocusInput::Left), id("b"));
+		assert_eq!(navigate(&targets, id("a"), FocusInput::Up), None);
+		assert_eq!(navigate(&targets, id("d"), FocusInput::Next), id("a"));
+		assert_eq!(navigate(&targets, id("a"), FocusInput::Previous), id("d"));
+	}
+
+	#[test]
+	fn adjustable_widgets_capture_the_knob() {
+		let focus = Focus::default();
+		let slider = Id::new("slider");
+		let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(50.0));
+		let frame = |inputs: &[FocusInput]| {
+			focus.register_adjustable(slider, rect);
+			focus.end_frame(inputs, false);
+		};
+
+		frame(&[FocusInput::Next, FocusInput::Activate, FocusInput::Next, FocusInput::Next]);
+		let response = focus.register_adjustable(slider, rect);
+		assert!(response.focused && response.captured);
+		assert_eq!(response.steps, 2);
+
+		focus.end_frame(&[FocusInput::Back], false);
+		frame(&[FocusInput::Previous]);
+		assert!(!focus.register_adjustable(slider, rect).captured);
+
+		focus.end_frame(&[], true);
+		assert_eq!(focus.focused_rect(), None);
+	}
+
+	#[test]
+	fn keys_map_to_focus_input() {
+		let shift = Modifiers {
+			shift: true,
+			..Default::default()
+		};
+		assert_eq!(FocusInput::from_key(Key::Tab, shift), Some(FocusInput::Previous));
+		assert_eq!(FocusInput::from_key(Key::PageDown, Modifiers::NONE), Some(FocusInput::Next));
+		assert_eq!(FocusInput::from_key(Key::Enter, Modifiers::NONE), Some(FocusInput::Activate));
+		assert_eq!(FocusInput::from_key(Key::A, Modifiers::NONE), None);
+	}
+}
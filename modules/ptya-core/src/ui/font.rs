@@ -1,11 +1,12 @@
 use std::sync::Arc;
-use log::info;
+use log::{error, info};
 use egui::text::{FontData, FontDefinitions};
 use egui::FontFamily;
 use anyways::ext::AuditExt;
 use crate::{AssetManager, Location};
 
-pub async fn load_fonts(asset: &AssetManager) -> anyways::Result<FontDefinitions> {
+/// Loads Roboto and the icons, `theme_font` replaces Roboto for regular text when it can be loaded.
+pub async fn load_fonts(asset: &AssetManager, theme_font: Option<&str>) -> anyways::Result<FontDefinitions> {
 	let fonts = vec![
 		"Roboto-ThinItalic",
 		"Roboto-Thin",
@@ -36,9 +37,21 @@ pub async fn load_fonts(asset: &AssetManager) -> anyways::Result<FontDefinitions
 		add_font(&mut fonts, font, &name);
 	}
 
+	let mut proportional = vec!["Roboto-Regular".to_string()];
+	if let Some(name) = theme_font {
+		// A missing theme font should not stop pitaya from starting.
+		match load_font(asset, name).await {
+			Ok((font, name)) => {
+				add_font(&mut fonts, font, &name);
+				proportional.insert(0, name);
+			}
+			Err(err) => error!("Failed to load the theme font {err:?}"),
+		}
+	}
+
 	fonts
 		.families
-		.insert(FontFamily::Proportional, vec!["Roboto-Regular".to_string()]);
+		.insert(FontFamily::Proportional, proportional);
 	fonts
 		.families
 		.insert(FontFamily::Monospace, vec!["Roboto-Regular".to_string()]);
@@ -0,0 +1,655 @@
+//! Touch gestures on top of the egui pointer.
+//! Widgets ask for the gestures they understand with [Pui::gestures](crate::ui::Pui::gestures).
+//! Every touch is recognized once and each gesture goes to a single widget,
+//! the innermost widget under the finger that listens for that kind of gesture.
+//! Drags and pinches stay with the widget that got them first until the finger is lifted,
+//! and edge swipes go before drags so they work over any content.
+//! Widgets that only drag along one axis let drags in the other direction through to the widgets below,
+//! so a vertical list inside horizontal pages can be scrolled and swiped.
+use crate::config::GestureConfig;
+use egui::mutex::Mutex;
+use egui::{Context, Id, Pos2, Rect, Vec2};
+
+/// The gestures a widget listens for.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct GestureSense {
+	pub tap: bool,
+	pub double_tap: bool,
+	pub long_press: bool,
+	/// Dragging and swiping with one finger.
+	pub drag: bool,
+	/// Only takes drags that start moving along this axis, [None] takes drags in any direction.
+	pub axis: Option<DragAxis>,
+	/// Pinching and rotating with two fingers.
+	pub pinch: bool,
+	/// Swiping in from an edge of the screen, the widget has to cover that edge.
+	pub edge: bool,
+}
+
+impl GestureSense {
+	pub fn tap() -> GestureSense {
+		GestureSense {
+			tap: true,
+			..Default::default()
+		}
+	}
+
+	pub fn drag() -> GestureSense {
+		GestureSense {
+			drag: true,
+			..Default::default()
+		}
+	}
+
+	/// Drags that start out sideways, like swiping between pages.
+	pub fn drag_x() -> GestureSense {
+		GestureSense {
+			drag: true,
+			axis: Some(DragAxis::Horizontal),
+			..Default::default()
+		}
+	}
+
+	/// Drags that start out up or down, like scrolling a list.
+	pub fn drag_y() -> GestureSense {
+		GestureSense {
+			drag: true,
+			axis: Some(DragAxis::Vertical),
+			..Default::default()
+		}
+	}
+
+	pub fn edge() -> GestureSense {
+		GestureSense {
+			edge: true,
+			..Default::default()
+		}
+	}
+
+	/// Checks if the widget takes a gesture, `movement` is how far the finger moved since it was pressed.
+	fn senses(&self, kind: GestureKind, movement: Vec2) -> bool {
+		match kind {
+			GestureKind::Tap => self.tap,
+			GestureKind::DoubleTap => self.double_tap,
+			GestureKind::LongPress => self.long_press,
+			GestureKind::Drag => {
+				self.drag
+					&& match self.axis {
+						None => true,
+						Some(DragAxis::Horizontal) => movement.x.abs() >= movement.y.abs(),
+						Some(DragAxis::Vertical) => movement.y.abs() > movement.x.abs(),
+					}
+			}
+			GestureKind::Pinch => self.pinch,
+			GestureKind::Edge => self.edge,
+		}
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DragAxis {
+	Horizontal,
+	Vertical,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Edge {
+	Left,
+	Right,
+	Top,
+	Bottom,
+}
+
+impl Edge {
+	/// The edge a position is close to, if any.
+	fn at(screen: Rect, pos: Pos2, size: f32) -> Option<Edge> {
+		if pos.x - screen.left() <= size {
+			Some(Edge::Left)
+		} else if screen.right() - pos.x <= size {
+			Some(Edge::Right)
+		} else if pos.y - screen.top() <= size {
+			Some(Edge::Top)
+		} else if screen.bottom() - pos.y <= size {
+			Some(Edge::Bottom)
+		} else {
+			None
+		}
+	}
+
+	/// How much of a movement goes away from the edge.
+	pub fn inward(self, movement: Vec2) -> f32 {
+		match self {
+			Edge::Left => movement.x,
+			Edge::Right => -movement.x,
+			Edge::Top => movement.y,
+			Edge::Bottom => -movement.y,
+		}
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct EdgeSwipe {
+	pub edge: Edge,
+	/// How far the finger moved away from the edge.
+	pub distance: f32,
+	/// The speed away from the edge in points per second.
+	pub velocity: f32,
+	/// The finger was lifted, this is the last event of the swipe.
+	pub released: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Gesture {
+	/// A tap is reported right away, a second tap reports both a tap and a double tap.
+	Tap(Pos2),
+	DoubleTap(Pos2),
+	LongPress(Pos2),
+	/// The movement of a finger since the last frame, after it has moved past the tap slop.
+	Drag(Vec2),
+	/// A drag was released faster than the swipe velocity, in points per second.
+	Swipe(Vec2),
+	/// Two or more fingers, the zoom factor and rotation in radians are since the last frame.
+	Pinch {
+		center: Pos2,
+		zoom: f32,
+		rotation: f32,
+		translation: Vec2,
+	},
+	Edge(EdgeSwipe),
+}
+
+impl Gesture {
+	fn kind(&self) -> GestureKind {
+		match self {
+			Gesture::Tap(_) => GestureKind::Tap,
+			Gesture::DoubleTap(_) => GestureKind::DoubleTap,
+			Gesture::LongPress(_) => GestureKind::LongPress,
+			Gesture::Drag(_) | Gesture::Swipe(_) => GestureKind::Drag,
+			Gesture::Pinch { .. } => GestureKind::Pinch,
+			Gesture::Edge(_) => GestureKind::Edge,
+		}
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum GestureKind {
+	Tap,
+	DoubleTap,
+	LongPress,
+	Drag,
+	Pinch,
+	Edge,
+}
+
+/// The gestures a widget got this frame.
+#[derive(Clone, Default, Debug)]
+pub struct GestureResponse {
+	pub gestures: Vec<Gesture>,
+}
+
+impl GestureResponse {
+	pub fn tapped(&self) -> Option<Pos2> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::Tap(pos) => Some(*pos),
+			_ => None,
+		})
+	}
+
+	pub fn double_tapped(&self) -> Option<Pos2> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::DoubleTap(pos) => Some(*pos),
+			_ => None,
+		})
+	}
+
+	pub fn long_pressed(&self) -> Option<Pos2> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::LongPress(pos) => Some(*pos),
+			_ => None,
+		})
+	}
+
+	pub fn drag_delta(&self) -> Vec2 {
+		self.gestures.iter().fold(Vec2::ZERO, |sum, gesture| match gesture {
+			Gesture::Drag(delta) => sum + *delta,
+			_ => sum,
+		})
+	}
+
+	/// The release velocity of a swipe.
+	pub fn swipe(&self) -> Option<Vec2> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::Swipe(velocity) => Some(*velocity),
+			_ => None,
+		})
+	}
+
+	/// The zoom factor of a pinch since the last frame, 1 without a pinch.
+	pub fn zoom(&self) -> f32 {
+		self.gestures
+			.iter()
+			.map(|gesture| match gesture {
+				Gesture::Pinch { zoom, .. } => *zoom,
+				_ => 1.0,
+			})
+			.product()
+	}
+
+	/// The rotation of a pinch since the last frame in radians.
+	pub fn rotation(&self) -> f32 {
+		self.gestures
+			.iter()
+			.map(|gesture| match gesture {
+				Gesture::Pinch { rotation, .. } => *rotation,
+				_ => 0.0,
+			})
+			.sum()
+	}
+
+	/// How far the fingers of a pinch moved together since the last frame.
+	pub fn pinch_translation(&self) -> Vec2 {
+		self.gestures.iter().fold(Vec2::ZERO, |sum, gesture| match gesture {
+			Gesture::Pinch { translation, .. } => sum + *translation,
+			_ => sum,
+		})
+	}
+
+	pub fn pinch_center(&self) -> Option<Pos2> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::Pinch { center, .. } => Some(*center),
+			_ => None,
+		})
+	}
+
+	pub fn edge_swipe(&self) -> Option<EdgeSwipe> {
+		self.gestures.iter().find_map(|gesture| match gesture {
+			Gesture::Edge(swipe) => Some(*swipe),
+			_ => None,
+		})
+	}
+}
+
+/// The pointer state of one frame, taken from egui.
+#[derive(Copy, Clone, Debug)]
+struct PointerFrame {
+	time: f64,
+	screen: Rect,
+	pressed: Option<Pos2>,
+	pos: Option<Pos2>,
+	released: bool,
+	velocity: Vec2,
+	// Center, zoom, rotation and translation of two or more fingers.
+	touch: Option<(Pos2, f32, f32, Vec2)>,
+}
+
+impl PointerFrame {
+	fn new(ctx: &Context) -> PointerFrame {
+		let input = ctx.input();
+		let pointer = &input.pointer;
+		PointerFrame {
+			time: input.time,
+			screen: input.screen_rect(),
+			pressed: pointer.any_pressed().then(|| pointer.press_origin()).flatten(),
+			pos: pointer.interact_pos(),
+			released: pointer.any_released(),
+			velocity: pointer.velocity(),
+			touch: input.multi_touch().map(|touch| {
+				(
+					pointer.interact_pos().unwrap_or(touch.start_pos),
+					touch.zoom_delta,
+					touch.rotation_delta,
+					touch.translation_delta,
+				)
+			}),
+		}
+	}
+}
+
+/// Turns the pointer of every frame into gestures, it does not know about widgets.
+#[derive(Default)]
+struct Recognizer {
+	origin: Option<Pos2>,
+	start: f64,
+	last: Pos2,
+	moved: bool,
+	long_pressed: bool,
+	multi: bool,
+	edge: Option<Edge>,
+	last_tap: Option<(f64, Pos2)>,
+}
+
+impl Recognizer {
+	fn update(&mut self, config: &GestureConfig, frame: &PointerFrame) -> Vec<Gesture> {
+		let mut gestures = Vec::new();
+		if let (None, Some(pos)) = (self.origin, frame.pressed) {
+			*self = Recognizer {
+				origin: Some(pos),
+				start: frame.time,
+				last: pos,
+				edge: Edge::at(frame.screen, pos, config.edge_size),
+				last_tap: self.last_tap,
+				..Default::default()
+			};
+		}
+		let origin = match self.origin {
+			Some(origin) => origin,
+			None => return gestures,
+		};
+
+		if let Some((center, zoom, rotation, translation)) = frame.touch {
+			self.multi = true;
+			gestures.push(Gesture::Pinch {
+				center,
+				zoom,
+				rotation,
+				translation,
+			});
+		}
+		if let Some(pos) = frame.pos {
+			if !self.moved && (pos - origin).length() > config.tap_slop {
+				self.moved = true;
+			}
+			if self.moved && !self.multi {
+				if let Some(edge) = self.edge {
+					gestures.push(Gesture::Edge(EdgeSwipe {
+						edge,
+						distance: edge.inward(pos - origin),
+						velocity: edge.inward(frame.velocity),
+						released: frame.released,
+					}));
+				}
+				gestures.push(Gesture::Drag(pos - self.last));
+			}
+			self.last = pos;
+		}
+
+		if frame.released {
+			if self.moved && !self.multi && frame.velocity.length() > config.swipe_velocity {
+				gestures.push(Gesture::Swipe(frame.velocity));
+			} else if !self.moved && !self.multi && !self.long_pressed {
+				gestures.push(Gesture::Tap(origin));
+				match self.last_tap {
+					Some((time, pos))
+						if frame.time - time <= config.double_tap_time
+							&& (origin - pos).length() <= config.tap_slop * 2.0 =>
+					{
+						gestures.push(Gesture::DoubleTap(origin));
+						self.last_tap = None;
+					}
+					_ => self.last_tap = Some((frame.time, origin)),
+				}
+			}
+			self.origin = None;
+		} else if !self.moved && !self.multi && !self.long_pressed && frame.time - self.start >= config.long_press_time {
+			self.long_pressed = true;
+			gestures.push(Gesture::LongPress(origin));
+		}
+		gestures
+	}
+
+	/// Checks if a finger is down that could still become a long press.
+	fn waiting(&self) -> bool {
+		self.origin.is_some() && !self.moved && !self.multi && !self.long_pressed
+	}
+}
+
+#[derive(Copy, Clone)]
+struct Detector {
+	id: Id,
+	rect: Rect,
+	sense: GestureSense,
+}
+
+#[derive(Default)]
+struct GestureState {
+	recognizer: Recognizer,
+	processed: bool,
+	// Widgets that asked for gestures this frame, in the order they did.
+	detectors: Vec<Detector>,
+	// The widgets from the last frame, gestures are handed out to them.
+	previous: Vec<Detector>,
+	// Widgets that got a drag, pinch or edge swipe keep it until the finger is lifted.
+	claims: Vec<(GestureKind, Id)>,
+	routed: Vec<(Id, Gesture)>,
+}
+
+/// Recognizes gestures once per frame and hands them out to the widgets that listen for them.
+pub struct Gestures {
+	pub config: GestureConfig,
+	inner: Mutex<GestureState>,
+}
+
+impl Gestures {
+	pub fn new(config: GestureConfig) -> Gestures {
+		Gestures {
+			config,
+			inner: Default::default(),
+		}
+	}
+
+	/// Gets the gestures for a widget this frame and listens for gestures in the rect from the next frame on.
+	/// Widgets shown later in the frame are considered to be on top of the ones before.
+	pub fn detect(&self, ctx: &Context, id: Id, rect: Rect, sense: GestureSense) -> GestureResponse {
+		let mut inner = self.inner.lock();
+		self.process(&mut inner, ctx);
+
+		inner.detectors.push(Detector { id, rect, sense });
+		GestureResponse {
+			gestures: inner
+				.routed
+				.iter()
+				.filter(|(target, _)| *target == id)
+				.map(|(_, gesture)| *gesture)
+				.collect(),
+		}
+	}
+
+	/// Recognizes the gestures of this frame once, the first widget that asks for gestures does this.
+	fn process(&self, inner: &mut GestureState, ctx: &Context) {
+		if inner.processed {
+			return;
+		}
+		inner.processed = true;
+		let frame = PointerFrame::new(ctx);
+		let origin = inner.recognizer.origin.or(frame.pressed);
+		let gestures = inner.recognizer.update(&self.config, &frame);
+		if inner.recognizer.waiting() {
+			ctx.request_repaint();
+		}
+		if let Some(origin) = origin {
+			let movement = inner.recognizer.last - origin;
+			inner.route(origin, movement, gestures);
+		}
+		if inner.recognizer.origin.is_none() {
+			inner.claims.clear();
+		}
+	}
+
+	/// Ends the frame, called by the system.
+	/// Frames that no widget asked for gestures in are recognized here, so a touch is never missed halfway through.
+	pub fn end_frame(&self, ctx: &Context) {
+		let mut inner = self.inner.lock();
+		self.process(&mut inner, ctx);
+		inner.previous = std::mem::take(&mut inner.detectors);
+		inner.routed.clear();
+		inner.processed = false;
+	}
+}
+
+impl GestureState {
+	fn route(&mut self, origin: Pos2, movement: Vec2, gestures: Vec<Gesture>) {
+		for gesture in gestures {
+			let kind = gesture.kind();
+			let continuous = matches!(kind, GestureKind::Drag | GestureKind::Pinch | GestureKind::Edge);
+			// A widget that took the edge swipe also takes the drag that comes with it.
+			if kind == GestureKind::Drag && self.claims.iter().any(|(claim, _)| *claim == GestureKind::Edge) {
+				continue;
+			}
+
+			let claimed = self
+				.claims
+				.iter()
+				.find(|(claim, _)| *claim == kind)
+				.map(|(_, id)| *id);
+			let target = claimed.or_else(|| {
+				self.previous
+					.iter()
+					.rev()
+					.find(|detector| detector.sense.senses(kind, movement) && detector.rect.contains(origin))
+					.map(|detector| detector.id)
+			});
+			if let Some(target) = target {
+				if continuous && claimed.is_none() {
+					self.claims.push((kind, target));
+				}
+				self.routed.push((target, gesture));
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn frame(time: f64) -> PointerFrame {
+		PointerFrame {
+			time,
+			screen: Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 600.0)),
+			pressed: None,
+			pos: None,
+			released: false,
+			velocity: Vec2::ZERO,
+			touch: None,
+		}
+	}
+
+	fn press(time: f64, pos: Pos2) -> PointerFrame {
+		PointerFrame {
+			pressed: Some(pos),
+			pos: Some(pos),
+			..frame(time)
+		}
+	}
+
+	fn release(time: f64, pos: Pos2, velocity: Vec2) -> PointerFrame {
+		PointerFrame {
+			pos: Some(pos),
+			released: true,
+			velocity,
+			..frame(time)
+		}
+	}
+
+	#[test]
+	fn taps_and_double_taps() {
+		let config = GestureConfig::default();
+		let mut recognizer = Recognizer::default();
+		let pos = Pos2::new(500.0, 300.0);
+		recognizer.update(&config, &press(0.0, pos));
+		assert_eq!(recognizer.update(&config, &release(0.1, pos, Vec2::ZERO)), vec![Gesture::Tap(pos)]);
+		recognizer.update(&config, &press(0.2, pos));
+		assert_eq!(
+			recognizer.update(&config, &release(0.25, pos, Vec2::ZERO)),
+			vec![Gesture::Tap(pos), Gesture::DoubleTap(pos)]
+		);
+
+		recognizer.update(&config, &press(2.0, pos));
+		assert_eq!(recognizer.update(&config, &frame(2.6)), vec![Gesture::LongPress(pos)]);
+		assert!(recognizer.update(&config, &release(2.7, pos, Vec2::ZERO)).is_empty());
+	}
+
+	#[test]
+	fn drags_become_swipes_when_released_fast() {
+		let config = GestureConfig::default();
+		let mut recognizer = Recognizer::default();
+		recognizer.update(&config, &press(0.0, Pos2::new(500.0, 300.0)));
+		let moved = recognizer.update(&config, &release(0.1, Pos2::new(400.0, 300.0), Vec2::new(-1000.0, 0.0)));
+		assert_eq!(
+			moved,
+			vec![Gesture::Drag(Vec2::new(-100.0, 0.0)), Gesture::Swipe(Vec2::new(-1000.0, 0.0))]
+		);
+	}
+
+	#[test]
+	fn edge_swipes_go_before_drags_of_inner_widgets() {
+		let config = GestureConfig::default();
+		let screen = frame(0.0).screen;
+		let mut state = GestureState {
+			previous: vec![
+				Detector {
+					id: Id::new("screen"),
+					rect: screen,
+					sense: GestureSense::edge(),
+				},
+				Detector {
+					id: Id::new("list"),
+					rect: screen,
+					sense: GestureSense::drag(),
+				},
+			],
+			..Default::default()
+		};
+		let origin = Pos2::new(990.0, 300.0);
+		state.recognizer.update(&config, &press(0.0, origin));
+		let gestures = state.recognizer.update(&config, &PointerFrame {
+			pos: Some(Pos2::new(900.0, 300.0)),
+			..frame(0.1)
+		});
+		state.route(origin, Pos2::new(900.0, 300.0) - origin, gestures);
+
+		assert_eq!(state.routed.len(), 1);
+		let (target, gesture) = state.routed[0];
+		assert_eq!(target, Id::new("screen"));
+		assert!(matches!(gesture, Gesture::Edge(EdgeSwipe { edge: Edge::Right, distance, .. }) if distance == 90.0));
+	}
+
+	#[test]
+	fn overlapping_drags_go_by_their_direction() {
+		let config = GestureConfig::default();
+		let (pages, list) = (Id::new("pages"), Id::new("list"));
+		let area = Rect::from_min_size(Pos2::new(100.0, 100.0), Vec2::new(600.0, 400.0));
+		let drag = |to: Pos2, then: Pos2| {
+			let mut state = GestureState {
+				previous: vec![
+					Detector {
+						id: pages,
+						rect: area,
+						sense: GestureSense::drag_x(),
+					},
+					Detector {
+						id: list,
+						rect: area.shrink(50.0),
+						sense: GestureSense::drag_y(),
+					},
+				],
+				..Default::default()
+			};
+			let origin = Pos2::new(400.0, 300.0);
+			state.recognizer.update(&config, &press(0.0, origin));
+			for (time, pos) in [(0.1, to), (0.2, then)] {
+				let gestures = state.recognizer.update(&config, &PointerFrame {
+					pos: Some(pos),
+					..frame(time)
+				});
+				state.route(origin, pos - origin, gestures);
+			}
+			state.routed.iter().map(|(target, _)| *target).collect::<Vec<_>>()
+		};
+
+		// The list is on top, but only takes drags that start out vertical.
+		assert_eq!(drag(Pos2::new(400.0, 360.0), Pos2::new(500.0, 360.0)), vec![list, list]);
+		assert_eq!(drag(Pos2::new(460.0, 300.0), Pos2::new(460.0, 400.0)), vec![pages, pages]);
+		// Vertical drags outside of the list go nowhere, the pages only swipe sideways.
+		let mut state = GestureState {
+			previous: vec![Detector {
+				id: pages,
+				rect: area,
+				sense: GestureSense::drag_x(),
+			}],
+			..Default::default()
+		};
+		state.route(Pos2::new(120.0, 120.0), Vec2::new(0.0, 50.0), vec![Gesture::Drag(Vec2::new(0.0, 50.0))]);
+		assert!(state.routed.is_empty());
+	}
+}
@@ -1,10 +1,11 @@
-use crate::color::{ColorState, Theme};
+use crate::color::{ColorState, Theme, Typography};
 use crate::ui::font::load_fonts;
+use crate::ui::gesture::{GestureResponse, GestureSense};
 use crate::{AssetManager, System};
 use anyways::ext::AuditExt;
 use egui::style::Spacing;
 use egui::FontFamily::Proportional;
-use egui::{Context, FontDefinitions, FontId, InnerResponse, LayerId, Rect, Rounding, Style, TextStyle, Ui, Vec2, Visuals};
+use egui::{Area, Context, FontDefinitions, FontId, Id, InnerResponse, LayerId, Order, Rect, Rounding, Style, TextStyle, Ui, Vec2, Visuals};
 use std::ops::{Deref, DerefMut};
 
 pub const SPACING_SIZE: f32 = 25.0;
@@ -19,7 +20,9 @@ pub const VISUAL_SIZE: f32 = 40.0;
 pub const INTERACTIVE_SIZE: f32 = 90.0;
 
 pub mod components;
+pub mod focus;
 mod font;
+pub mod gesture;
 pub mod util;
 
 #[macro_export]
@@ -122,6 +125,35 @@ impl<'t, 'mgr, 'egui> Pui<'t, 'mgr, 'egui> {
         })
     }
 
+    /// Shows content on the foreground over a part of the screen, it takes input before the content below.
+    /// This is an [Area] and not just a layer because egui only hit tests areas.
+    pub fn overlay<R>(
+        &mut self,
+        id: Id,
+        rect: Rect,
+        add_contents: impl FnOnce(&mut Pui<'t, 'mgr, '_>) -> R,
+    ) -> InnerResponse<R> {
+        let ctx = self.ctx().clone();
+        Area::new(id)
+            .order(Order::Foreground)
+            .fixed_pos(rect.min)
+            .show(&ctx, |ui| {
+                ui.set_min_size(rect.size());
+                let mut pui = Pui {
+                    color: self.color,
+                    sys: self.sys,
+                    ui,
+                };
+                add_contents(&mut pui)
+            })
+    }
+
+    /// Gets the gestures for a widget, see [gesture] for which widget gets a gesture.
+    pub fn gestures(&self, id: Id, rect: Rect, sense: GestureSense) -> GestureResponse {
+        let rect = rect.intersect(self.clip_rect());
+        self.sys.gesture.detect(self.ctx(), id, rect, sense)
+    }
+
     pub fn child<'nt, 'negui>(
         &self,
         new_ui: &'negui mut Ui,
@@ -158,16 +190,21 @@ impl<'t, 'mgr, 'egui> DerefMut for Pui<'t, 'mgr, 'egui> {
 
 pub struct UiAssets {
     fonts: FontDefinitions,
+    scale: f32,
 }
 
 impl UiAssets {
-    pub async fn new(asset: &AssetManager) -> anyways::Result<UiAssets> {
+    pub async fn new(asset: &AssetManager, typography: &Typography) -> anyways::Result<UiAssets> {
         Ok(UiAssets {
-            fonts: load_fonts(asset).await.wrap_err("Failed to load fonts")?
+            fonts: load_fonts(asset, typography.font.as_deref())
+                .await
+                .wrap_err("Failed to load fonts")?,
+            scale: typography.scale,
         })
     }
 
     pub fn apply(self, ctx: Context) {
+        let scale = self.scale;
         ctx.set_fonts(self.fonts);
         ctx.set_style(Style {
             text_styles: [
@@ -185,6 +222,7 @@ impl UiAssets {
                 (TextStyle::Button, FontId::new(35.0, Proportional)),
                 (TextStyle::Small, FontId::new(30.0, Proportional)),
             ]
+                .map(|(style, font)| (style, FontId::new(font.size * scale, font.family)))
                 .into(),
             spacing: Spacing {
                 item_spacing: Vec2::new(25.0, 25.0),
@@ -1,8 +1,9 @@
-use egui::{Align2, Color32, FontFamily, FontId, Painter, Pos2, Rect, Response, Rounding, Sense, Ui, Vec2};
+use egui::{Align2, Color32, FontFamily, FontId, Painter, Pos2, Rect, Response, Rounding, Sense, Vec2};
 use egui::text::LayoutJob;
-use crate::ui::{INTERACTIVE_SIZE, ROUNDING, SPACING_SIZE};
+use crate::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
 
-pub fn alloc_intractable(ui: &mut Ui, content_width: f32) -> (Rect, Response, Rounding) {
+/// Allocates an interactive component, the rounding comes from the theme.
+pub fn alloc_intractable(ui: &mut Pui, content_width: f32) -> (Rect, Response, Rounding) {
     let (rect, response) = ui.allocate_at_least(
         Vec2::new(
             SPACING_SIZE + 5.0 + content_width + 5.0 + SPACING_SIZE,
@@ -11,7 +12,7 @@ pub fn alloc_intractable(ui: &mut Ui, content_width: f32) -> (Rect, Response, Ro
         Sense::click_and_drag(),
     );
 
-    (rect, response, ROUNDING)
+    (rect, response, ui.color().rounding())
 }
 
 // icon is the Code point of the icon which you can get at https://fonts.google.com/icons.
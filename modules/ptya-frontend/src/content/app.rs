@@ -1,13 +1,13 @@
 use crate::AppDropper;
-use egui::{pos2, Id, LayerId, Mesh, Order, Rect, Rgba, Rounding, Sense, Vec2};
+use egui::{pos2, LayerId, Mesh, Order, Rect, Rgba, Rounding, Sense, Vec2};
 use epaint::{Color32, RectShape, Tessellator};
 use glium::framebuffer::SimpleFrameBuffer;
 use glium::texture::{MipmapsOption, SrgbFormat, SrgbTexture2d};
 use glium::Surface;
-use ptya_core::animation::{Animation, AnimationImpl, Easing, Lerp};
+use ptya_core::animation::{Animation, AnimationImpl, AnimationRef, Easing, Lerp, Spring};
 use ptya_core::app::{App, AppContainer, AppId};
 use ptya_core::color::ColorTag;
-use ptya_core::ui::{Pui, INTERACTIVE_SIZE, ROUNDING, SPACING_SIZE};
+use ptya_core::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
 use ptya_core::System;
 use std::rc::Rc;
 use log::warn;
@@ -15,7 +15,11 @@ use log::warn;
 pub struct AppPanel {
 	id: AppId,
 	rect: Rect,
-	animation: Option<Id>,
+	animation: Option<AnimationRef<Rect>>,
+	// Pointer velocity the panel was thrown with, applied when it starts moving.
+	fling: Vec2,
+	// The rect being faded out of when reduced motion is on.
+	fade: Option<(Rect, AnimationRef<f32>)>,
 }
 
 impl AppPanel {
@@ -24,51 +28,106 @@ impl AppPanel {
 			id,
 			rect: from,
 			animation: None,
+			fling: Vec2::ZERO,
+			fade: None,
 		}
 	}
 
-	fn get_ani(ui: &mut Pui, rect: Rect, id: Id) -> Animation<Rect> {
+	/// Throws the panel with a velocity in points per second.
+	pub fn fling(&mut self, velocity: Vec2) {
+		self.fling = velocity;
+	}
+
+	fn get_ani(ui: &mut Pui, rect: Rect, handle: AnimationRef<Rect>) -> Animation<Rect> {
 		ui.sys
 			.animation
-			.get_or::<Rect>(id, || AnimationImpl::simple(rect))
+			.get_ref_or(handle, || AnimationImpl::spring(rect, Spring::SNAPPY))
 	}
 
 	pub fn set_rect(&mut self, ui: &mut Pui, rect: Rect) {
-		if let Some(id) = self.animation {
-			if Self::get_ani(ui, rect, id).is_finished() {
+		if let Some(handle) = self.animation {
+			if Self::get_ani(ui, rect, handle).is_finished() {
 				self.animation = None;
 			}
 		}
+		if let Some((_, handle)) = self.fade {
+			if ui.sys.animation.get_ref(handle).is_finished() {
+				self.fade = None;
+			}
+		}
 
-		if self.rect != rect && self.animation.is_none() {
-			let id = self.id.egui_id().with("rect_animation");
-			Self::get_ani(ui, rect, id)
-				.set_from(self.rect)
-				.set_to(rect)
-				.set_easing(Easing::EaseInOut)
+		if self.rect != rect && ui.sys.animation.reduced_motion() {
+			// Fade out and back in at the new rect instead of moving there.
+			let from = self.get_rect(ui);
+			let handle = AnimationRef::new(self.id.egui_id().with("rect_fade"));
+			ui.sys
+				.animation
+				.get_ref(handle)
+				.set_easing(Easing::Linear)
+				.set_from(0.0)
+				.set_to(1.0)
 				.begin();
 
-			self.animation = Some(id);
+			self.fade = Some((from, handle));
+			self.animation = None;
+			self.fling = Vec2::ZERO;
+			self.rect = rect;
+		} else if self.rect != rect {
+			let handle = AnimationRef::new(self.id.egui_id().with("rect_animation"));
+			let mut animation = Self::get_ani(ui, self.rect, handle);
+			if self.animation.is_none() {
+				// The animation may still hold a rect from a previous panel of this app.
+				animation.set_value(self.rect);
+			}
+
+			// The spring keeps its momentum if the layout changes while it is still moving.
+			animation.retarget(rect);
+			if self.fling != Vec2::ZERO {
+				let distance = rect.center() - self.rect.center();
+				if distance.length_sq() > 1.0 {
+					let velocity = self.fling.dot(distance) / distance.length_sq();
+					animation.set_velocity(velocity as f64);
+				}
+				self.fling = Vec2::ZERO;
+			}
+
+			self.animation = Some(handle);
 			self.rect = rect;
 		}
 	}
 
 	pub fn get_rect(&mut self, ui: &mut Pui) -> Rect {
-		if let Some(id) = self.animation {
-			Self::get_ani(ui, self.rect, id).get_value()
+		if let Some((from, handle)) = self.fade {
+			if ui.sys.animation.get_ref(handle).get_value() < 0.5 {
+				return from;
+			}
+		}
+
+		if let Some(handle) = self.animation {
+			Self::get_ani(ui, self.rect, handle).get_value()
 		} else {
 			self.rect
 		}
 	}
 
+	/// Gets how visible the panel is, this only drops below 1 while cross-fading.
+	fn get_opacity(&self, ui: &mut Pui) -> f32 {
+		match self.fade {
+			Some((_, handle)) => (ui.sys.animation.get_ref(handle).get_value() * 2.0 - 1.0).abs(),
+			None => 1.0,
+		}
+	}
+
 	pub fn draw(
 		&mut self,
 		ui: &mut Pui,
 		dropper: &mut Option<AppDropper>,
 	) -> Result<(), AppResponse> {
+		let backdrop = ui.color().bg();
 		let mut ui = ui.ascend(1.0);
 		if let Some(container) = ui.sys.app.apps().get_mut(&self.id) {
 			let rect = self.get_rect(&mut ui);
+			let opacity = self.get_opacity(&mut ui);
 
 			ui.allocate_ui_at_rect(rect, |eui| {
 				eui.set_clip_rect(rect);
@@ -78,6 +137,11 @@ impl AppPanel {
 			})
 			.inner?;
 
+			if opacity < 1.0 {
+				ui.painter()
+					.rect_filled(rect, ui.color().rounding(), backdrop.linear_multiply(1.0 - opacity));
+			}
+
 			//let mut eui = ui.child_ui_with_id_source(rect, Layout::default(), self.id.egui_id().with("ui"));
 			//eui.set_clip_rect(rect);
 		}
@@ -103,7 +167,8 @@ impl AppPanel {
 			app.dirty = true;
 		}
 
-		ui.painter().rect_filled(rect, ROUNDING, ui.color().bg());
+		let rounding = ui.color().rounding();
+		ui.painter().rect_filled(rect, rounding, ui.color().bg());
 
 		if let Some(id) = app.id {
 			let ctx = ui.ctx();
@@ -119,7 +184,7 @@ impl AppPanel {
 			tessellator.tessellate_rect(
 				&RectShape {
 					rect,
-					rounding: ROUNDING,
+					rounding,
 					fill: Color32::WHITE,
 					stroke: Default::default(),
 				},
@@ -149,8 +214,9 @@ impl AppPanel {
 		dropper: &mut Option<AppDropper>,
 	) -> Result<(), AppResponse> {
 		let id = ui.id().with("window");
+		let rounding = ui.color().rounding();
 
-		let mut animation = ui.sys().animation.get(id);
+		let mut animation = ui.sys().animation.get_ref(AnimationRef::<f32>::new(id));
 		if dropper.is_some() {
 			animation.redirect(0.0);
 		} else {
@@ -158,7 +224,7 @@ impl AppPanel {
 		}
 		let v = animation.get_value();
 		let size = Vec2::new((INTERACTIVE_SIZE * 0.75) * v, (INTERACTIVE_SIZE * 0.75) * v);
-		let rect = Rect::from_min_size(app_rect.right_top() - Vec2::new(size.x, 0.0) + Vec2::new(-(ROUNDING.nw * 0.3) * (1.0 - v), (ROUNDING.nw * 0.3) * (1.0 - v)), size);
+		let rect = Rect::from_min_size(app_rect.right_top() - Vec2::new(size.x, 0.0) + Vec2::new(-(rounding.nw * 0.3) * (1.0 - v), (rounding.nw * 0.3) * (1.0 - v)), size);
 
 		let color = ui.color().ascend(2.0).tag_bg(ColorTag::Secondary);
 		let response = ui.interact(rect, id, Sense::click_and_drag());
@@ -172,7 +238,7 @@ impl AppPanel {
 				&Rounding {
 					nw: 0.0,
 					se: 0.0,
-					..ROUNDING
+					..rounding
 				},
 				v,
 			),
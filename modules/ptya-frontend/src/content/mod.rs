@@ -6,6 +6,7 @@ use egui::style::Margin;
 use egui::{Frame, Pos2, Rect, Vec2};
 use log::{debug, info};
 use ptya_core::app::AppId;
+use ptya_core::config::LayoutConfig;
 use ptya_core::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE, VISUAL_SIZE};
 use ptya_core::System;
 
@@ -52,7 +53,9 @@ impl Content {
                                 Vec2::new(VISUAL_SIZE, VISUAL_SIZE),
                             ),
                             dropper.id.clone(),
+                            dropper.velocity,
                         );
+                        system.save_layout(self.layout());
                     }
                 }
 
@@ -85,6 +88,26 @@ impl Content {
             });
     }
 
+    /// Replaces the open apps with the layout saved in the current profile.
+    pub fn load_layout(&mut self, system: &System) {
+        let from = Rect::from_center_size(system.egui_ctx.input().screen_rect().center(), Vec2::ZERO);
+        let apps = system.app.apps();
+        let panel = |id: &String| {
+            let id = AppId { id: id.clone() };
+            apps.contains_key(&id).then(|| AppPanel::new(system, id, from))
+        };
+
+        self.primary = system.layout.primary.as_ref().and_then(panel);
+        self.widgets = system.layout.widgets.iter().filter_map(panel).collect();
+    }
+
+    fn layout(&self) -> LayoutConfig {
+        LayoutConfig {
+            primary: self.primary.as_ref().map(|app| app.id().id.clone()),
+            widgets: self.widgets.iter().map(|app| app.id().id.clone()).collect(),
+        }
+    }
+
     #[allow(clippy::blocks_in_if_conditions)]
     fn update_layout(&mut self, ui: &mut Pui, rect: Rect, dropper: &mut Option<AppDropper>) {
         let mut widget_width = 0.0;
@@ -264,6 +287,7 @@ impl Content {
         location: NewAppLocation,
         mut summon_rect: Rect,
         id: AppId,
+        velocity: Vec2,
     ) {
         let existing = self.find_app(&id);
         if let Some(app) = existing {
@@ -271,7 +295,8 @@ impl Content {
                 summon_rect = value.get_rect(ui);
             }
         }
-        let app = AppPanel::new(ui.sys, id, summon_rect);
+        let mut app = AppPanel::new(ui.sys, id, summon_rect);
+        app.fling(velocity);
         match location {
             NewAppLocation::Existing(AppLocation::Primary) => {
                 // If its a primary app then we can just remove and not worry about it.
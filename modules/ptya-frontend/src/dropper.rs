@@ -2,18 +2,24 @@ use crate::content::NewAppLocation;
 use egui::epaint::ahash::AHashMap;
 use egui::{Color32, Id, LayerId, Order, Pos2, Rect, Rounding, Stroke, Vec2};
 use log::debug;
-use ptya_core::animation::{Animation, Easing, Lerp};
+use ptya_core::animation::{Animation, AnimationRef, Easing, Keyframes, Lerp, Motion, Timeline};
 use ptya_core::app::AppId;
-use ptya_core::ui::ROUNDING;
 use ptya_core::System;
 use std::collections::hash_map::Entry;
 
+// Delay between each placement fading in, in animation lengths.
+const STAGGER_DELAY: f64 = 0.3;
+
 pub struct AppDropper {
 	pub pos: Pos2,
+	/// The pointer velocity in points per second.
+	pub velocity: Vec2,
 	pub id: AppId,
 	pub dropped: bool,
 	pub just_dropped: Option<NewAppLocation>,
 	placements: AHashMap<NewAppLocation, Placement>,
+	// The placements in the order they fade in, set on the first frame.
+	intro: Option<Vec<NewAppLocation>>,
 }
 
 impl AppDropper {
@@ -21,13 +27,24 @@ impl AppDropper {
 		debug!("Started app dropping of {id:?}");
 		AppDropper {
 			pos: Default::default(),
+			velocity: Vec2::ZERO,
 			id,
 			dropped: false,
 			just_dropped: None,
 			placements: AHashMap::new(),
+			intro: None,
 		}
 	}
 
+	/// Places an app without dragging it, like when its sidebar entry is activated with the knob.
+	pub fn place(id: AppId, pos: Pos2, location: NewAppLocation) -> AppDropper {
+		let mut dropper = AppDropper::new(id);
+		dropper.pos = pos;
+		dropper.dropped = true;
+		dropper.just_dropped = Some(location);
+		dropper
+	}
+
 	pub fn add_placement(&mut self, placement: Placement) {
 		if !self.dropped {
 			match self.placements.entry(placement.location) {
@@ -51,6 +68,9 @@ impl AppDropper {
 			if let Some(value) = input.pointer.interact_pos() {
 				self.pos = value;
 			}
+			if input.pointer.primary_down() {
+				self.velocity = input.pointer.velocity();
+			}
 
 			if !input.pointer.primary_down() {
 				debug!("Dropped {:?}", self.id);
@@ -75,15 +95,41 @@ impl AppDropper {
 		// Drain placements that have not been called this tick
 		//self.placements.drain_filter(|v, placement| !placement.keep);
 
+		let mut intro = sys
+			.animation
+			.get_timeline::<f32>(Id::new("pitaya@dropper_intro"), Timeline::new);
+		if self.intro.is_none() && !self.placements.is_empty() {
+			let mut order: Vec<_> = self.placements.keys().copied().collect();
+			order.sort();
+			intro
+				.set_timeline(Timeline::stagger(
+					order.iter().map(|_| {
+						Keyframes::new(0.0).then(1.0, 1.0, Easing::Motion(Motion::Decelerate))
+					}),
+					STAGGER_DELAY,
+				))
+				.play();
+			self.intro = Some(order);
+		}
+
 		let mut finished = self.dropped;
-		for (_, placement) in &self.placements {
+		for (location, placement) in &self.placements {
 			let color = sys.color.theme().tertiary.color;
 
+			// Placements that show up later do not take part in the intro.
+			let intro_v = self
+				.intro
+				.iter()
+				.flatten()
+				.position(|v| v == location)
+				.and_then(|track| intro.get_value(track))
+				.unwrap_or(1.0);
 			let v = placement
 				.get_animation(sys)
 				.redirect(placement.keep as u8 as f32)
 				.set_easing(Easing::EaseInOut)
-				.get_value();
+				.get_value()
+				* intro_v;
 
 			if v != 0.0 {
 				finished = false;
@@ -96,9 +142,15 @@ impl AppDropper {
 				.get_value()
 				.max(1.0 - v);
 
+			// With reduced motion placements only fade in instead of growing out of their origin.
+			let rect = if sys.animation.reduced_motion() {
+				placement.rect
+			} else {
+				Rect::lerp_static(&placement.from, &placement.rect, v)
+			};
 			painter.rect(
-				Rect::lerp_static(&placement.from, &placement.rect, v),
-				ROUNDING,
+				rect,
+				Rounding::same(sys.color.theme().rounding),
 				color.linear_multiply((0.05 + (0.05 * hover)) * v),
 				Stroke::new((2.5 + (2.5 * hover)) * v, color),
 			)
@@ -135,15 +187,15 @@ impl Placement {
 		}
 	}
 
-	fn id(&self) -> egui::Id {
-		Id::new(self.location)
+	fn handle(&self) -> AnimationRef<f32> {
+		AnimationRef::new(Id::new(self.location))
 	}
 
 	fn get_animation(&self, sys: &System) -> Animation<f32> {
-		sys.animation.get::<f32>(self.id())
+		sys.animation.get_ref(self.handle())
 	}
 
 	fn get_hover_animation(&self, sys: &System) -> Animation<f32> {
-		sys.animation.get::<f32>(self.id().with("hover"))
+		sys.animation.get_ref(self.handle().with("hover"))
 	}
 }
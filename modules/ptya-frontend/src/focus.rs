@@ -0,0 +1,66 @@
+use egui::{Color32, Id, LayerId, Order, Rect, Rounding, Stroke};
+use ptya_core::animation::{AnimationImpl, AnimationRef, Spring};
+use ptya_core::System;
+
+/// The gap between a focused widget and the ring around it.
+const GAP: f32 = 6.0;
+const WIDTH: f32 = 4.0;
+
+/// Draws the ring around the focused widget, it glides from widget to widget as the focus moves.
+pub struct FocusRing {
+	// Where the ring was last, so it can fade out there.
+	rect: Option<Rect>,
+}
+
+impl FocusRing {
+	pub fn new() -> FocusRing {
+		FocusRing { rect: None }
+	}
+
+	pub fn tick(&mut self, system: &System) {
+		let id = Id::new("pitaya@focus");
+		let focused = system.focus.focused_rect();
+		let mut shown = system.animation.get_ref(AnimationRef::<f32>::new(id.with("shown")));
+		shown.redirect(focused.is_some() as u8 as f32);
+		let appearing = *shown.get_to() == 1.0 && shown.get_value() == 0.0;
+		let shown = shown.get_value();
+
+		let target = match focused.or(self.rect) {
+			Some(rect) => rect.expand(GAP),
+			None => return,
+		};
+		self.rect = focused.or(self.rect);
+
+		let mut rect = system
+			.animation
+			.get_ref_or(AnimationRef::new(id), || AnimationImpl::spring(target, Spring::SNAPPY));
+		// The ring shows up on the focused widget instead of flying in from where it was hidden.
+		if appearing || system.animation.reduced_motion() {
+			rect.set_value(target);
+		} else if *rect.get_to() != target {
+			rect.retarget(target);
+		}
+		let rect = rect.get_value();
+		if shown == 0.0 {
+			return;
+		}
+
+		let theme = system.color.theme();
+		let color = theme.primary.color;
+		// A widget that has captured the knob is filled so it is clear turning adjusts it.
+		let fill = if system.focus.is_captured() {
+			color.linear_multiply(0.15 * shown)
+		} else {
+			Color32::TRANSPARENT
+		};
+		system
+			.egui_ctx
+			.layer_painter(LayerId::new(Order::Tooltip, id))
+			.rect(
+				rect,
+				Rounding::same(theme.rounding + GAP),
+				fill,
+				Stroke::new(WIDTH, color.linear_multiply(shown)),
+			);
+	}
+}
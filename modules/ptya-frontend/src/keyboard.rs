@@ -0,0 +1,164 @@
+use egui::{Align2, FontFamily, FontId, Frame, Id, Rect, Sense, TopBottomPanel, Vec2};
+use ptya_core::animation::{AnimationRef, Easing, Lerp, Motion};
+use ptya_core::color::{ColorState, LEVEL_MENU};
+use ptya_core::keyboard::{Key, KeyCap, KeyboardLayout, SUGGESTIONS};
+use ptya_core::ui::components::Text;
+use ptya_core::ui::util::draw_icon;
+use ptya_core::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE, VISUAL_SIZE};
+use ptya_core::System;
+use ptya_icon::icon;
+
+/// Space between keys.
+const GAP: f32 = 10.0;
+const KEY_HEIGHT: f32 = INTERACTIVE_SIZE;
+
+/// The on-screen keyboard, it slides up from the bottom while a text field has it open.
+pub struct KeyboardPanel {}
+
+impl KeyboardPanel {
+	pub fn new() -> KeyboardPanel {
+		KeyboardPanel {}
+	}
+
+	pub fn tick(&mut self, system: &System) {
+		let keyboard = &system.keyboard;
+		let mut open = system.animation.get_ref(AnimationRef::<f32>::new(Id::new("pitaya@keyboard")));
+		open.set_easing(Easing::Motion(Motion::Standard))
+			.redirect(keyboard.is_open() as u8 as f32);
+		let open = open.get_value();
+		if open == 0.0 {
+			return;
+		}
+
+		let rows = keyboard.layout().rows(keyboard.shift());
+		// The suggestions take up a row above the keys.
+		let height = SPACING_SIZE * 2.0 + (rows.len() + 1) as f32 * (KEY_HEIGHT + GAP) - GAP;
+		let color = system.color.new_state().at_level(LEVEL_MENU);
+		TopBottomPanel::bottom("keyboard")
+			.height_range(height * open..=height * open)
+			.resizable(false)
+			.frame(Frame::none().fill(color.bg()))
+			.show(&system.egui_ctx, |ui| {
+				keyboard.set_panel(ui.max_rect());
+				let mut ui = Pui::new(ui, system, color);
+				// Laid out from the bottom so the keys slide in with the panel.
+				let panel = ui.max_rect();
+				let area = Rect::from_min_max(
+					panel.left_bottom() - Vec2::new(0.0, height),
+					panel.right_bottom(),
+				)
+				.shrink(SPACING_SIZE);
+
+				let top = Rect::from_min_size(area.min, Vec2::new(area.width(), KEY_HEIGHT));
+				self.suggestions(&mut ui, top);
+
+				let units = rows
+					.iter()
+					.map(|row| row.iter().map(|cap| cap.width).sum::<f32>())
+					.fold(0.0, f32::max);
+				let unit = (area.width() + GAP) / units;
+				for (y, row) in rows.iter().enumerate() {
+					let row_width = row.iter().map(|cap| cap.width).sum::<f32>() * unit - GAP;
+					let mut x = area.min.x + (area.width() - row_width) / 2.0;
+					let top = area.min.y + (y + 1) as f32 * (KEY_HEIGHT + GAP);
+					for (i, cap) in row.iter().enumerate() {
+						let rect = Rect::from_min_size(
+							egui::pos2(x, top),
+							Vec2::new(cap.width * unit - GAP, KEY_HEIGHT),
+						);
+						self.key(&mut ui, rect, Id::new("pitaya@keyboard").with(y).with(i), cap);
+						x += cap.width * unit;
+					}
+				}
+			});
+	}
+
+	fn suggestions(&self, ui: &mut Pui, rect: Rect) {
+		let keyboard = &ui.sys().keyboard;
+		let width = rect.width() / SUGGESTIONS as f32;
+		for (i, word) in keyboard.suggestions().into_iter().enumerate() {
+			let rect = Rect::from_min_size(
+				rect.min + Vec2::new(width * i as f32, 0.0),
+				Vec2::new(width, rect.height()),
+			);
+			let id = Id::new("pitaya@keyboard_suggestion").with(i);
+			let response = ui.interact(rect, id, Sense::click());
+			if response.clicked() {
+				keyboard.suggest(&word);
+			}
+
+			let color = ui.color();
+			let text = Text::new(ui, word, None, font());
+			text.draw(ui.painter(), rect.center(), Align2::CENTER_CENTER, color.fg);
+		}
+	}
+
+	fn key(&self, ui: &mut Pui, rect: Rect, id: Id, cap: &KeyCap) {
+		let keyboard = &ui.sys().keyboard;
+		let response = ui.interact(rect, id, Sense::click());
+
+		// Keys flash and sink in when pressed, fingers cover the key so it has to be visible around them.
+		let mut press = ui.sys().animation.get_ref(AnimationRef::<f32>::new(id));
+		if response.clicked() {
+			keyboard.press(&cap.key);
+			press
+				.set_easing(Easing::Motion(Motion::Decelerate))
+				.set_from(1.0)
+				.set_to(0.0)
+				.begin_with_speed(2.0);
+		}
+		let pressed = if response.is_pointer_button_down_on() {
+			1.0
+		} else {
+			press.get_value()
+		};
+
+		let color = ui.color().ascend(1.0);
+		let group = match cap.key {
+			Key::Enter => &color.primary,
+			Key::Shift if keyboard.shift() => &color.secondary,
+			_ => &color.tertiary,
+		};
+		let (bg, fg) = match cap.key {
+			Key::Enter => (group.color, group.on_color),
+			Key::Shift if keyboard.shift() => (group.color_container, group.on_color_container),
+			_ => (color.bg(), color.fg),
+		};
+		let bg = bg.lerp(&group.color_container, pressed);
+		let fg = fg.lerp(&group.on_color_container, pressed);
+
+		let rect = rect.shrink(4.0 * pressed);
+		ui.painter().rect_filled(rect, rounding(&color), bg);
+		match Self::label(&cap.key) {
+			Ok(icon) => draw_icon(ui.painter(), icon, rect.center(), VISUAL_SIZE, fg),
+			Err(label) => {
+				let text = Text::new(ui, label, None, font());
+				text.draw(ui.painter(), rect.center(), Align2::CENTER_CENTER, fg);
+			}
+		}
+	}
+
+	/// The icon of a key, or its text for keys without one.
+	fn label(key: &Key) -> Result<u32, String> {
+		match key {
+			Key::Char(c) => Err(c.to_string()),
+			Key::Backspace => Ok(icon!("backspace")),
+			Key::Space => Ok(icon!("space_bar")),
+			Key::Enter => Ok(icon!("keyboard_return")),
+			Key::Shift => Ok(icon!("keyboard_capslock")),
+			Key::Hide => Ok(icon!("keyboard_hide")),
+			Key::Layout(KeyboardLayout::Qwerty) => Err("ABC".to_string()),
+			Key::Layout(KeyboardLayout::Numeric) => Err("123".to_string()),
+			Key::Layout(KeyboardLayout::Symbols) => Err("?123".to_string()),
+		}
+	}
+}
+
+fn font() -> FontId {
+	FontId::new(40.0, FontFamily::Name("Roboto-Medium".into()))
+}
+
+fn rounding(color: &ColorState) -> egui::Rounding {
+	// Keys sit close together, full rounding would make them look like pills.
+	egui::Rounding::same(color.theme.rounding / 2.0)
+}
@@ -3,9 +3,13 @@
 
 use crate::content::Content;
 use crate::dropper::AppDropper;
+use crate::focus::FocusRing;
+use crate::keyboard::KeyboardPanel;
+use crate::profile::ProfilePicker;
 use crate::sidebar::Sidebar;
 use anyways::ext::AuditExt;
 use anyways::Result;
+use egui::{Align2, Color32, FontId, Id, LayerId, Order};
 use glium::backend::Context;
 use log::info;
 use ptya_core::System;
@@ -13,6 +17,9 @@ use std::rc::Rc;
 
 mod content;
 mod dropper;
+mod focus;
+mod keyboard;
+mod profile;
 mod sidebar;
 
 const DEBUG_MODE: bool = false;
@@ -22,6 +29,9 @@ pub struct Frontend {
 	sidebar: Sidebar,
 	content: Content,
 	dropper: Option<AppDropper>,
+	keyboard: KeyboardPanel,
+	focus_ring: FocusRing,
+	profile_picker: Option<ProfilePicker>,
 }
 
 impl Frontend {
@@ -31,6 +41,9 @@ impl Frontend {
 			sidebar: Sidebar::new(),
 			content: Content::new(),
 			dropper: None,
+			keyboard: KeyboardPanel::new(),
+			focus_ring: FocusRing::new(),
+			profile_picker: None,
 		})
 	}
 
@@ -42,7 +55,10 @@ impl Frontend {
 				self.system.egui_ctx.request_repaint();
 			}
 
-			self.sidebar.tick(&self.system, &mut self.dropper);
+			// The keyboard goes first so the other panels make room for it.
+			self.keyboard.tick(&self.system);
+			self.sidebar
+				.tick(&self.system, &mut self.dropper, &mut self.profile_picker);
 			self.content.tick(&self.system, &mut self.dropper);
 
 			let mut finished = false;
@@ -54,24 +70,56 @@ impl Frontend {
 				info!("App dropper sequence finished");
 				self.dropper = None;
 			}
+
+			if DEBUG_MODE {
+				self.draw_debug();
+			}
+
+			if let Some(picker) = &mut self.profile_picker {
+				if picker.tick(&mut self.system) {
+					self.profile_picker = None;
+				}
+			}
+
+			// Drawn last so it knows where everything that can be focused is this frame.
+			self.focus_ring.tick(&self.system);
 		}
 
 		// Updated
+		let booting = !self.system.is_loaded();
 		if self.system.tick()? {
-			self.system.app.load_app(
-				&self.system.gl_ctx,
-				ptya_playground::manifest(),
-				ptya_playground::load(),
-			);
-			//self.system.app.load_app(
-			//	&self.system.gl_ctx,
+			self.system.load_app(ptya_playground::manifest(), ptya_playground::load());
+			//self.system.load_app(
 			//	ptya_map::manifest(),
 			//	ptya_map::load(&self.system).wrap_err("Failed to initialize map application")?,
 			//);
 			self.system.app.update(&self.system);
 			self.sidebar.update(&self.system);
+			self.content.load_layout(&self.system);
+			self.dropper = None;
+
+			let profiles = &self.system.profiles;
+			if booting && profiles.pick_on_boot && profiles.profiles.len() > 1 {
+				self.profile_picker = Some(ProfilePicker::new());
+			}
 		}
 
 		Ok(())
 	}
+
+	fn draw_debug(&self) {
+		let ctx = &self.system.egui_ctx;
+		let stats = self.system.animation.stats();
+		ctx.layer_painter(LayerId::new(Order::Debug, Id::new("pitaya@debug")))
+			.text(
+				ctx.input().screen_rect().right_top(),
+				Align2::RIGHT_TOP,
+				format!(
+					"Animations: {} live, {} active (frame {})",
+					stats.live, stats.active, stats.frame
+				),
+				FontId::monospace(20.0),
+				Color32::WHITE,
+			);
+	}
 }
@@ -0,0 +1,81 @@
+use egui::{Area, Order};
+use log::warn;
+use ptya_core::color::ColorTag;
+use ptya_core::layout;
+use ptya_core::ui::components::{Button, Toggle};
+use ptya_core::ui::Pui;
+use ptya_core::System;
+
+/// A fullscreen picker to switch between driver profiles.
+pub struct ProfilePicker {}
+
+enum PickerAction {
+	Select(String),
+	Create,
+	Close,
+}
+
+impl ProfilePicker {
+	pub fn new() -> ProfilePicker {
+		ProfilePicker {}
+	}
+
+	/// Returns true when the picker is done and should be closed.
+	pub fn tick(&mut self, system: &mut System) -> bool {
+		let screen = system.egui_ctx.input().screen_rect();
+		let color = system.color.new_state().dialog();
+		let active = system.asset.profile().to_string();
+		let mut pick_on_boot = system.profiles.pick_on_boot;
+
+		let mut action = None;
+		Area::new("profile_picker")
+			.order(Order::Foreground)
+			.fixed_pos(screen.min)
+			.show(&system.egui_ctx.clone(), |ui| {
+				ui.set_min_size(screen.size());
+				ui.painter().rect_filled(screen, 0.0, color.bg());
+
+				let ui = &mut Pui::new(ui, system, color);
+				layout!(ui => vertical_centered {
+					for profile in &ui.sys.profiles.profiles {
+						let mut button = Button::new(&profile.name, ColorTag::Tertiary);
+						if profile.id == active {
+							button.color = ColorTag::Secondary;
+						}
+
+						if button.ui(ui).clicked() {
+							action = Some(PickerAction::Select(profile.id.clone()));
+						}
+					}
+
+					if Button::new("New profile", ColorTag::Tertiary).ui(ui).clicked() {
+						action = Some(PickerAction::Create);
+					}
+					Toggle::new(&mut pick_on_boot, "Ask on every boot").show(ui);
+					if Button::new("Close", ColorTag::Red).ui(ui).clicked() {
+						action = Some(PickerAction::Close);
+					}
+				});
+			});
+
+		if pick_on_boot != system.profiles.pick_on_boot {
+			system.set_pick_on_boot(pick_on_boot);
+		}
+
+		let result = match action {
+			Some(PickerAction::Select(id)) if id == active => Ok(()),
+			Some(PickerAction::Select(id)) => system.switch_profile(id),
+			Some(PickerAction::Create) => {
+				let name = format!("Driver {}", system.profiles.profiles.len() + 1);
+				system.create_profile(name)
+			}
+			Some(PickerAction::Close) => Ok(()),
+			None => return false,
+		};
+
+		if result.is_err() {
+			warn!("A profile is already being loaded");
+		}
+		true
+	}
+}
@@ -2,23 +2,25 @@ use egui::{Rect, Response, Sense, Vec2};
 use log::warn;
 use ptya_core::app::AppId;
 use ptya_core::ui::components::ProgressSpinner;
+use ptya_core::ui::focus::{FocusResponse, Focusable};
 use ptya_core::ui::util::draw_icon;
-use ptya_core::ui::{Pui, INTERACTIVE_SIZE, ROUNDING, VISUAL_SIZE};
+use ptya_core::ui::{Pui, INTERACTIVE_SIZE, VISUAL_SIZE};
 
 pub struct SidebarEntry {
 	pub id: AppId,
 }
 
 impl SidebarEntry {
-	pub fn draw(&mut self, ui: &mut Pui) -> Response {
+	pub fn draw(&mut self, ui: &mut Pui) -> (Response, FocusResponse) {
 		const SIZE: f32 = INTERACTIVE_SIZE * 1.15;
 		let (rect, response) =
 			ui.allocate_exact_size(Vec2::new(SIZE, SIZE), Sense::click_and_drag());
+		let (response, focus) = response.focus(ui);
 
 		if let Some(app) = ui.sys.app.apps().get_mut(&self.id) {
 			// Render panel
 			let color = ui.color().ascend(1.0);
-			ui.painter().rect_filled(rect, ROUNDING, color.bg());
+			ui.painter().rect_filled(rect, color.rounding(), color.bg());
 			let pos = rect.center();
 
 			draw_icon(
@@ -36,6 +38,6 @@ impl SidebarEntry {
 			);
 		}
 
-		response
+		(response, focus)
 	}
 }
@@ -1,11 +1,16 @@
 mod entry;
 
+use crate::content::{AppLocation, NewAppLocation};
 use crate::dropper::AppDropper;
+use crate::profile::ProfilePicker;
 use crate::sidebar::entry::SidebarEntry;
 use egui::panel::Side;
 use egui::style::Margin;
-use egui::{Context, Frame, Vec2};
+use egui::{Context, Frame, Layout, Sense, Vec2};
+use ptya_core::ui::focus::Focusable;
+use ptya_core::ui::util::draw_icon;
 use ptya_core::ui::{Pui, INTERACTIVE_SIZE, SPACING_SIZE};
+use ptya_icon::icon;
 use ptya_core::System;
 
 pub struct Sidebar {
@@ -28,7 +33,12 @@ impl Sidebar {
 		}
 	}
 
-	pub fn tick(&mut self, system: &System, dropper: &mut Option<AppDropper>) {
+	pub fn tick(
+		&mut self,
+		system: &System,
+		dropper: &mut Option<AppDropper>,
+		profile_picker: &mut Option<ProfilePicker>,
+	) {
 		let color = system.color.new_state().ascend(1.0);
 		egui::SidePanel::new(self.side, "sidebar")
 			.frame(Frame {
@@ -41,11 +51,47 @@ impl Sidebar {
 			.show(&system.egui_ctx, |ui| {
 				let mut ui = Pui::new(ui, system, color);
 				for entry in &mut self.entries {
-					let response = entry.draw(&mut ui);
+					let (response, focus) = entry.draw(&mut ui);
 					if response.drag_started() {
 						*dropper = Some(AppDropper::new(entry.id.clone()));
+					} else if focus.activated && dropper.is_none() {
+						// Apps can not be dragged with the knob, activating an entry opens it as the primary app.
+						*dropper = Some(AppDropper::place(
+							entry.id.clone(),
+							response.rect.center(),
+							NewAppLocation::Existing(AppLocation::Primary),
+						));
 					}
 				}
+
+				ui.with_layout(Layout::bottom_up(egui::Align::Center), |ui| {
+					let ui = &mut Pui::new(ui, system, color);
+					const SIZE: f32 = INTERACTIVE_SIZE * 1.15;
+					let (rect, response) =
+						ui.allocate_exact_size(Vec2::new(SIZE, SIZE), Sense::click());
+					let response = response.focusable(ui);
+					let color = color.ascend(1.0);
+					ui.painter().rect_filled(rect, color.rounding(), color.bg());
+					draw_icon(ui.painter(), icon!("account_circle"), rect.center(), SIZE * 0.6, color.fg);
+					if response.clicked() && profile_picker.is_none() {
+						*profile_picker = Some(ProfilePicker::new());
+					}
+
+					let (rect, response) =
+						ui.allocate_exact_size(Vec2::new(SIZE, SIZE), Sense::click());
+					let response = response.focusable(ui);
+					let reduced_motion = system.animation.reduced_motion();
+					let icon = if reduced_motion {
+						icon!("motion_photos_off")
+					} else {
+						icon!("animation")
+					};
+					ui.painter().rect_filled(rect, color.rounding(), color.bg());
+					draw_icon(ui.painter(), icon, rect.center(), SIZE * 0.6, color.fg);
+					if response.clicked() {
+						system.animation.set_reduced_motion(!reduced_motion);
+					}
+				});
 			});
 	}
 }